@@ -1,25 +1,55 @@
-use crate::{engine::Engine, error::Error, model, tensor::Tensor};
+use crate::{
+    engine::Engine,
+    error::Error,
+    model::{self, IoDescriptor, Model},
+    ops::{self, OpContext, OpRegistry},
+    profile::{LayerProfile, Profile},
+    tensor::{Tensor, TensorMut},
+};
 use deepviewrt_sys as ffi;
 use std::{
-    cell::{Cell, RefCell},
-    ffi::CString,
-    ptr,
+    cell::Cell,
+    ffi::{c_void, CString},
+    marker::PhantomData,
+    mem, ptr,
 };
 
-pub struct Context {
+/// Minimum size of the memory provided to [`ContextBuilder::context_memory`],
+/// matches `NN_CONTEXT_SIZEOF` from the DeepViewRT headers.
+pub const CONTEXT_SIZEOF: usize = 512;
+
+/// Selects how much cache memory [`Context::for_model`] reserves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// The model's minimum cache size.
+    Minimum,
+    /// The model's optimum cache size.
+    Optimum,
+    /// An explicit cache size in bytes.
+    Bytes(usize),
+}
+
+/// A DeepViewRT context.  The lifetime ties the context to any caller
+/// provided memory given to [`ContextBuilder`], contexts created through
+/// [`Context::new`] allocate their own memory and are unconstrained.
+pub struct Context<'a> {
     owned: bool,
     ptr: *mut ffi::NNContext,
     engine: Cell<Option<Engine>>,
-    model: Vec<u8>,
-    tensors: RefCell<Vec<(i32, Tensor)>>,
+    model: Option<Model>,
+    tensors: Vec<Option<Tensor>>,
+    cache: Option<Tensor>,
+    mempool: Option<Tensor>,
+    ops: Option<Box<OpRegistry>>,
+    _memory: PhantomData<&'a mut [u8]>,
 }
 
-impl Context {
+impl<'a> Context<'a> {
     pub fn new(
         engine: Option<Engine>,
         memory_size: usize,
         cache_size: usize,
-    ) -> Result<Context, Error> {
+    ) -> Result<Self, Error> {
         let ret = unsafe {
             ffi::nn_context_init(
                 match &engine {
@@ -37,26 +67,74 @@ impl Context {
                 "nn_context_init returned null",
             )));
         }
-        let tensors_ref: Vec<(i32, Tensor)> = Vec::new();
-        let tensors = RefCell::new(tensors_ref);
-        Ok(Context {
-            owned: true,
-            ptr: ret,
+        Ok(unsafe { Self::wrap(ret, true, engine) })
+    }
+
+    /// Creates a builder for contexts placed in caller provided memory.
+    pub fn builder() -> ContextBuilder<'a> {
+        ContextBuilder::default()
+    }
+
+    unsafe fn wrap(ptr: *mut ffi::NNContext, owned: bool, engine: Option<Engine>) -> Self {
+        let cache = Tensor::from_ptr(ffi::nn_context_cache(ptr), false).ok();
+        let mempool = Tensor::from_ptr(ffi::nn_context_mempool(ptr), false).ok();
+        let mut context = Context {
+            owned,
+            ptr,
             engine: Cell::new(engine),
-            model: Vec::new(),
-            tensors,
-        })
+            model: None,
+            tensors: Vec::new(),
+            cache,
+            mempool,
+            ops: None,
+            _memory: PhantomData,
+        };
+        context.load_tensors();
+        context
     }
 
-    /*
-    pub fn cache(&self) -> Option<Tensor> {
+    /// Builds the table of tensor handles for the model currently loaded into
+    /// the context, indexed by layer.
+    fn load_tensors(&mut self) {
+        self.tensors.clear();
+        let model = unsafe { ffi::nn_context_model(self.ptr) };
+        if model.is_null() {
+            return;
+        }
+        let layer_count = unsafe { ffi::nn_model_layer_count(model) };
+        self.tensors = (0..layer_count)
+            .map(|index| unsafe {
+                Tensor::from_ptr(ffi::nn_context_tensor_index(self.ptr, index), false).ok()
+            })
+            .collect();
+    }
 
+    /// Creates a context with the memory pool and cache sized from the model's
+    /// requirements and then loads the model into it.
+    pub fn for_model(
+        engine: Option<Engine>,
+        model: Model,
+        cache: CachePolicy,
+    ) -> Result<Self, Error> {
+        let cache_size = match cache {
+            CachePolicy::Minimum => model.cache_minimum_size(),
+            CachePolicy::Optimum => model.cache_optimum_size(),
+            CachePolicy::Bytes(size) => size,
+        };
+        let mut context = Context::new(engine, model.memory_size(), cache_size)?;
+        context.load_model(model)?;
+        Ok(context)
     }
 
-    pub fn mempool(&self) -> Option<Tensor> {
+    /// The context's cache tensor, if a cache was configured.
+    pub fn cache(&self) -> Option<&Tensor> {
+        self.cache.as_ref()
+    }
 
+    /// The context's memory pool tensor, if a memory pool was configured.
+    pub fn mempool(&self) -> Option<&Tensor> {
+        self.mempool.as_ref()
     }
-    */
 
     pub fn engine(&self) -> Option<&Engine> {
         let engine_ptr = self.engine.as_ptr();
@@ -76,192 +154,448 @@ impl Context {
         unsafe { (*self.engine.as_ptr()).as_ref() }
     }
 
-    pub fn model(&self) -> &Vec<u8> {
-        &self.model
+    pub fn model(&self) -> Option<&Model> {
+        self.model.as_ref()
+    }
+
+    fn loaded_model(&self) -> Result<&Model, Error> {
+        match &self.model {
+            Some(model) => Ok(model),
+            None => Err(Error::WrapperError(String::from("no model loaded"))),
+        }
+    }
+
+    fn input_layer(&self, index: usize) -> Result<usize, Error> {
+        match self.loaded_model()?.inputs().get(index) {
+            Some(layer) => Ok(*layer as usize),
+            None => Err(Error::WrapperError(format!("input {} out of range", index))),
+        }
+    }
+
+    fn output_layer(&self, index: usize) -> Result<usize, Error> {
+        match self.loaded_model()?.outputs().get(index) {
+            Some(layer) => Ok(*layer as usize),
+            None => Err(Error::WrapperError(format!(
+                "output {} out of range",
+                index
+            ))),
+        }
+    }
+
+    fn input_layer_by_name(&self, name: &str) -> Result<usize, Error> {
+        let model = self.loaded_model()?;
+        let layer = model.layer_lookup(name)? as u32;
+        if !model.inputs().contains(&layer) {
+            return Err(Error::WrapperError(format!(
+                "{} is not a model input",
+                name
+            )));
+        }
+        Ok(layer as usize)
+    }
+
+    fn output_layer_by_name(&self, name: &str) -> Result<usize, Error> {
+        let model = self.loaded_model()?;
+        let layer = model.layer_lookup(name)? as u32;
+        if !model.outputs().contains(&layer) {
+            return Err(Error::WrapperError(format!(
+                "{} is not a model output",
+                name
+            )));
+        }
+        Ok(layer as usize)
+    }
+
+    /// Describes the inputs of the loaded model.
+    pub fn inputs(&self) -> Result<Vec<IoDescriptor>, Error> {
+        self.loaded_model()?.input_descriptors()
+    }
+
+    /// Describes the outputs of the loaded model.
+    pub fn outputs(&self) -> Result<Vec<IoDescriptor>, Error> {
+        self.loaded_model()?.output_descriptors()
     }
 
     pub fn input(&self, index: usize) -> Result<&Tensor, Error> {
-        let inputs = model::inputs(&self.model)?;
-        self.tensor_index(inputs[index] as usize)
+        let layer = self.input_layer(index)?;
+        self.tensor_index(layer)
     }
 
-    pub fn input_mut(&mut self, index: usize) -> Result<&mut Tensor, Error> {
-        let inputs = model::inputs(&self.model)?;
-        self.tensor_index_mut(inputs[index] as usize)
+    pub fn input_mut(&mut self, index: usize) -> Result<TensorMut<'_>, Error> {
+        let layer = self.input_layer(index)?;
+        self.tensor_index_mut(layer)
+    }
+
+    pub fn input_by_name(&self, name: &str) -> Result<&Tensor, Error> {
+        let layer = self.input_layer_by_name(name)?;
+        self.tensor_index(layer)
+    }
+
+    pub fn input_by_name_mut(&mut self, name: &str) -> Result<TensorMut<'_>, Error> {
+        let layer = self.input_layer_by_name(name)?;
+        self.tensor_index_mut(layer)
     }
 
     pub fn output(&self, index: usize) -> Result<&Tensor, Error> {
-        let outputs = model::outputs(&self.model)?;
-        self.tensor_index(outputs[index] as usize)
-    }
-
-    pub fn output_mut(&mut self, index: usize) -> Result<&mut Tensor, Error> {
-        let outputs = model::outputs(&self.model)?;
-        self.tensor_index_mut(outputs[index] as usize)
-    }
-
-    // pub fn model(&self) -> Option<&Model> {
-    //     if !self.model.as_ptr().is_null() {
-    //         let model_ref = unsafe { &*self.model.as_ptr() };
-    //         if model_ref.is_some() {
-    //             return model_ref.as_ref();
-    //         }
-    //     }
-
-    //     let ret = unsafe { ffi::nn_context_model(self.ptr) };
-    //     if ret.is_null() {
-    //         return None;
-    //     }
-    //     let model = match unsafe { Model::try_from_ptr(ret) } {
-    //         Ok(model) => Some(model),
-    //         Err(e) => {
-    //             eprintln!("{}", e);
-    //             None
-    //         }
-    //     };
-    //     self.model.set(model);
-
-    //     let model_ref = unsafe { &*self.model.as_ptr() };
-    //     if model_ref.is_some() {
-    //         return model_ref.as_ref();
-    //     }
-    //     None
-    // }
-
-    pub fn load_model(&mut self, model: Vec<u8>) -> Result<(), Error> {
+        let layer = self.output_layer(index)?;
+        self.tensor_index(layer)
+    }
+
+    pub fn output_mut(&mut self, index: usize) -> Result<TensorMut<'_>, Error> {
+        let layer = self.output_layer(index)?;
+        self.tensor_index_mut(layer)
+    }
+
+    pub fn output_by_name(&self, name: &str) -> Result<&Tensor, Error> {
+        let layer = self.output_layer_by_name(name)?;
+        self.tensor_index(layer)
+    }
+
+    pub fn output_by_name_mut(&mut self, name: &str) -> Result<TensorMut<'_>, Error> {
+        let layer = self.output_layer_by_name(name)?;
+        self.tensor_index_mut(layer)
+    }
+
+    /// Loads the model into the context.  The model buffer is validated again
+    /// before loading as a memory mapped model may have changed on disk since
+    /// it was first opened.  An invalid model leaves the current one loaded.
+    pub fn load_model(&mut self, model: Model) -> Result<(), Error> {
+        model::validate(model.as_bytes())?;
         self.unload_model();
-        self.model = model;
+        let bytes = model.as_bytes();
         let ret = unsafe {
             ffi::nn_context_model_load(
                 self.ptr,
-                self.model.len(),
-                self.model.as_ptr() as *const std::ffi::c_void,
+                bytes.len(),
+                bytes.as_ptr() as *const std::ffi::c_void,
             )
         };
         if ret != ffi::NNError_NN_SUCCESS {
             return Err(Error::from(ret));
         }
+        self.model = Some(model);
+        self.load_tensors();
         Ok(())
     }
 
     pub fn unload_model(&mut self) {
         unsafe { ffi::nn_context_model_unload(self.ptr) };
-        let tensors_ref: Vec<(i32, Tensor)> = Vec::new();
-        self.tensors = RefCell::new(tensors_ref);
-        self.model = Vec::new();
-        // self.model.set(None);
+        self.tensors.clear();
+        self.model = None;
     }
 
-    pub fn run(&self) -> Result<(), Error> {
-        let err = unsafe { ffi::nn_context_run(self.ptr) };
+    /// Runs the model.  The context is borrowed mutably so no tensor mapping
+    /// can be alive while the library writes the layer outputs.
+    pub fn run(&mut self) -> Result<(), Error> {
+        let ptr = self.ptr;
+        let err = self.with_ops(|| unsafe { ffi::nn_context_run(ptr) });
         if err != ffi::NNError_NN_SUCCESS {
             return Err(Error::from(err));
         }
         Ok(())
     }
 
-    pub fn tensor(&self, name: &str) -> Result<Tensor, Error> {
-        let cname = match CString::new(name) {
-            Ok(cname) => cname,
-            Err(e) => return Err(Error::WrapperError(e.to_string())),
-        };
-
-        let ret = unsafe { ffi::nn_context_tensor(self.ptr, cname.into_raw()) };
-        if ret.is_null() {
-            return Err(Error::WrapperError(String::from("No tensor found")));
+    /// Runs the single layer at `index`.  Layers must be stepped in order as
+    /// each layer consumes the outputs of the layers before it.
+    pub fn step(&mut self, index: usize) -> Result<(), Error> {
+        let err = self.step_raw(index);
+        if err != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(err));
         }
+        Ok(())
+    }
 
-        // let index = match self.model() {
-        //     Some(model) => model.layer_lookup(name),
-        //     None => return Err(Error::WrapperError(String::from("Could not get
-        // index"))), }
-        // .unwrap();
+    fn step_raw(&mut self, index: usize) -> ffi::NNError {
+        let ptr = self.ptr;
+        self.with_ops(|| unsafe { ffi::nn_context_step(ptr, index) })
+    }
 
-        let cname = CString::new(name).unwrap();
-        let tensor_ptr = unsafe { ffi::nn_context_tensor(self.ptr, cname.into_raw()) };
-        if tensor_ptr.is_null() {
-            return Err(Error::WrapperError(format!("tensor not found: {}", name)));
+    /// Calls `f` with the model and tensor table lent to the registered ops,
+    /// which the library may invoke while `f` runs the context.
+    fn with_ops<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        match &self.ops {
+            Some(registry) => registry.bind(self.model.as_ref(), &mut self.tensors, f),
+            None => f(),
         }
+    }
 
-        let tensor = unsafe { Tensor::from_ptr(tensor_ptr, false).unwrap() };
-        Ok(tensor)
+    /// Returns an iterator which runs the model one layer at a time.  The
+    /// context may be inspected between steps through [`Steps::context`] to
+    /// capture intermediate activations.  Iteration ends after the last layer
+    /// or the first layer which fails.
+    pub fn steps(&mut self) -> Steps<'_, 'a> {
+        Steps {
+            context: self,
+            index: 0,
+            done: false,
+        }
     }
 
-    pub fn tensor_index_mut(&mut self, index: usize) -> Result<&mut Tensor, Error> {
-        let ret = unsafe { ffi::nn_context_tensor_index(self.ptr, index) };
-        if ret.is_null() {
-            return Err(Error::WrapperError(String::from("No tensor found")));
+    /// Runs every layer up to and including `layer`.
+    pub fn run_until(&mut self, layer: usize) -> Result<(), Error> {
+        for index in 0..=layer {
+            self.step(index)?;
         }
-        let tensor = unsafe { Tensor::from_ptr(ret, false).unwrap() };
+        Ok(())
+    }
 
-        match self.tensors.try_borrow_mut() {
-            Ok(mut borrowed) => {
-                borrowed.push((index as i32, tensor));
-            }
-            Err(e) => {
-                return Err(Error::WrapperError(e.to_string()));
+    /// Registers a custom operator implementation for layers whose op is
+    /// `name`.  Errors returned by the operator are reported to the library
+    /// as the closest matching `NNError` code.
+    pub fn register_op<F>(&mut self, name: &str, op: F) -> Result<(), Error>
+    where
+        F: Fn(&mut OpContext<'_>) -> Result<(), Error> + 'static,
+    {
+        match &mut self.ops {
+            Some(registry) => registry.insert(name, op),
+            None => {
+                let mut registry = Box::<OpRegistry>::default();
+                registry.insert(name, op);
+                ops::register(self.ptr, &registry)?;
+                self.ops = Some(registry);
             }
         }
-        let tensors_ref = self.tensors.get_mut();
-        {
-            for (index_, tensor) in tensors_ref {
-                if index_ == &(index as i32) {
-                    return Ok(tensor);
-                }
-            }
+        Ok(())
+    }
 
-            Err(Error::WrapperError(String::from("Tensor not found")))
+    /// Collects the per-layer timings recorded by the last [`Context::run`] or
+    /// the layers run so far through [`Context::steps`].
+    pub fn profile(&self) -> Result<Profile, Error> {
+        let model = self.loaded_model()?;
+        let mut layers = Vec::with_capacity(model.layer_count());
+        for layer in model.layers() {
+            let tensor = self.tensor_index(layer.index())?;
+            layers.push(LayerProfile {
+                index: layer.index(),
+                name: layer.name()?.to_string(),
+                layer_type: layer.layer_type()?.to_string(),
+                compute_ns: tensor.time(),
+                io_ns: tensor.io_time(),
+                shape: tensor.shape().to_vec(),
+            });
         }
+        Ok(Profile { layers })
     }
 
-    pub fn tensor_index(&self, index: usize) -> Result<&Tensor, Error> {
-        let ret = unsafe { ffi::nn_context_tensor_index(self.ptr, index) };
-        if ret.is_null() {
-            return Err(Error::WrapperError(String::from("No tensor found")));
+    fn tensor_lookup(&self, name: &str) -> Result<usize, Error> {
+        let cname = match CString::new(name) {
+            Ok(cname) => cname,
+            Err(e) => return Err(Error::WrapperError(e.to_string())),
+        };
+        let model = unsafe { ffi::nn_context_model(self.ptr) };
+        if model.is_null() {
+            return Err(Error::WrapperError(String::from("no model loaded")));
+        }
+        let ret = unsafe { ffi::nn_model_layer_lookup(model, cname.as_ptr()) };
+        if ret < 0 {
+            return Err(Error::WrapperError(format!("tensor not found: {}", name)));
         }
-        let tensor = unsafe { Tensor::from_ptr(ret, false).unwrap() };
+        Ok(ret as usize)
+    }
 
-        match self.tensors.try_borrow_mut() {
-            Ok(mut borrowed) => {
-                borrowed.push((index as i32, tensor));
-            }
-            Err(e) => {
-                return Err(Error::WrapperError(e.to_string()));
-            }
+    pub fn tensor(&self, name: &str) -> Result<&Tensor, Error> {
+        let index = self.tensor_lookup(name)?;
+        self.tensor_index(index)
+    }
+
+    pub fn tensor_mut(&mut self, name: &str) -> Result<TensorMut<'_>, Error> {
+        let index = self.tensor_lookup(name)?;
+        self.tensor_index_mut(index)
+    }
+
+    pub fn tensor_index(&self, index: usize) -> Result<&Tensor, Error> {
+        match self.tensors.get(index) {
+            Some(Some(tensor)) => Ok(tensor),
+            _ => Err(Error::WrapperError(format!("No tensor found at {}", index))),
         }
-        let tensors_ref = unsafe { &*self.tensors.as_ptr() };
-        {
-            for (index_, tensor) in tensors_ref {
-                if index_ == &(index as i32) {
-                    return Ok(tensor);
-                }
-            }
+    }
 
-            Err(Error::WrapperError(String::from("Tensor not found")))
+    pub fn tensor_index_mut(&mut self, index: usize) -> Result<TensorMut<'_>, Error> {
+        match self.tensors.get_mut(index) {
+            Some(Some(tensor)) => Ok(TensorMut::from(tensor)),
+            _ => Err(Error::WrapperError(format!("No tensor found at {}", index))),
         }
     }
 
+    /// Wraps a raw context pointer without taking ownership.
+    ///
+    /// # Safety
+    /// The pointer must reference a valid context which outlives the returned
+    /// object.
     pub unsafe fn from_ptr(ptr: *mut ffi::NNContext) -> Result<Self, Error> {
         if ptr.is_null() {
             return Err(Error::WrapperError(String::from("ptr is null")));
         }
 
-        let tensors_ref: Vec<(i32, Tensor)> = Vec::new();
-        let tensors = RefCell::new(tensors_ref);
+        Ok(Self::wrap(ptr, false, None))
+    }
+}
 
-        Ok(Self {
-            owned: false,
-            ptr,
-            engine: Cell::new(None),
-            model: Vec::new(),
-            // model: Cell::new(None),
-            tensors,
+/// The result of running a single layer through [`Context::steps`].
+#[derive(Debug)]
+pub struct Step {
+    pub index: usize,
+    pub name: Option<String>,
+    pub result: Result<(), Error>,
+}
+
+/// Iterator returned by [`Context::steps`], which holds the context's
+/// exclusive borrow so no tensor mapping is alive while a layer runs.
+pub struct Steps<'c, 'a> {
+    context: &'c mut Context<'a>,
+    index: usize,
+    done: bool,
+}
+
+impl<'a> Steps<'_, 'a> {
+    /// The context being stepped, for inspecting tensors between steps.
+    pub fn context(&self) -> &Context<'a> {
+        self.context
+    }
+}
+
+impl Iterator for Steps<'_, '_> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let index = self.index;
+        let err = self.context.step_raw(index);
+        if err == ffi::NNError_NN_ERROR_INVALID_LAYER {
+            self.done = true;
+            return None;
+        }
+
+        let result = if err == ffi::NNError_NN_SUCCESS {
+            Ok(())
+        } else {
+            self.done = true;
+            Err(Error::from(err))
+        };
+        self.index += 1;
+
+        let name = match self.context.model() {
+            Some(model) => model.layer_name(index).ok().map(str::to_string),
+            None => None,
+        };
+        Some(Step {
+            index,
+            name,
+            result,
         })
     }
 }
 
-impl Drop for Context {
+/// Builds a [`Context`] using `nn_context_init_ex` so the context structure,
+/// memory pool and cache can be placed in caller provided memory such as huge
+/// pages or a reserved carve-out.  Any memory not provided is allocated by the
+/// library using the configured sizes.
+#[derive(Default)]
+pub struct ContextBuilder<'a> {
+    engine: Option<Engine>,
+    context_memory: Option<&'a mut [u8]>,
+    memory_size: usize,
+    memory: Option<&'a mut [u8]>,
+    cache_size: usize,
+    cache: Option<&'a mut [u8]>,
+}
+
+impl<'a> ContextBuilder<'a> {
+    pub fn engine(mut self, engine: Engine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Memory used for the context structure itself, it must be at least
+    /// [`CONTEXT_SIZEOF`] bytes and pointer aligned.
+    pub fn context_memory(mut self, memory: &'a mut [u8]) -> Self {
+        self.context_memory = Some(memory);
+        self
+    }
+
+    /// Size of the memory pool allocated by the library.  Ignored when a
+    /// buffer is provided through [`ContextBuilder::memory`].
+    pub fn memory_size(mut self, size: usize) -> Self {
+        self.memory_size = size;
+        self
+    }
+
+    /// Memory used as the context's memory pool.
+    pub fn memory(mut self, memory: &'a mut [u8]) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Size of the cache allocated by the library.  Ignored when a buffer is
+    /// provided through [`ContextBuilder::cache`].
+    pub fn cache_size(mut self, size: usize) -> Self {
+        self.cache_size = size;
+        self
+    }
+
+    /// Memory used as the context's cache.
+    pub fn cache(mut self, cache: &'a mut [u8]) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn build(self) -> Result<Context<'a>, Error> {
+        let context_memory = match self.context_memory {
+            Some(memory) => {
+                if memory.len() < CONTEXT_SIZEOF {
+                    return Err(Error::WrapperError(format!(
+                        "context memory must be at least {} bytes",
+                        CONTEXT_SIZEOF
+                    )));
+                }
+                if memory.as_ptr().align_offset(mem::align_of::<usize>()) != 0 {
+                    return Err(Error::WrapperError(String::from(
+                        "context memory must be pointer aligned",
+                    )));
+                }
+                memory.as_mut_ptr() as *mut c_void
+            }
+            None => ptr::null_mut(),
+        };
+        let (memory_size, memory) = match self.memory {
+            Some(memory) => (memory.len(), memory.as_mut_ptr() as *mut c_void),
+            None => (self.memory_size, ptr::null_mut()),
+        };
+        let (cache_size, cache) = match self.cache {
+            Some(cache) => (cache.len(), cache.as_mut_ptr() as *mut c_void),
+            None => (self.cache_size, ptr::null_mut()),
+        };
+
+        let ret = unsafe {
+            ffi::nn_context_init_ex(
+                context_memory,
+                match &self.engine {
+                    Some(engine) => engine.to_ptr_mut(),
+                    None => ptr::null_mut(),
+                },
+                memory_size,
+                memory,
+                cache_size,
+                cache,
+            )
+        };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "nn_context_init_ex returned null",
+            )));
+        }
+        Ok(unsafe { Context::wrap(ret, true, self.engine) })
+    }
+}
+
+impl Drop for Context<'_> {
     fn drop(&mut self) {
+        if self.ops.is_some() {
+            ops::unregister(self.ptr);
+        }
         if self.owned {
             unsafe { ffi::nn_context_release(self.ptr) };
         }
@@ -59,10 +59,14 @@ impl Engine {
         Some(version_cstr.to_str().unwrap())
     }
 
+    /// # Safety
+    /// The returned pointer is only valid for the lifetime of the engine.
     pub unsafe fn to_ptr(&self) -> *const ffi::NNEngine {
         self.ptr
     }
 
+    /// # Safety
+    /// The returned pointer is only valid for the lifetime of the engine.
     pub unsafe fn to_ptr_mut(&self) -> *mut ffi::NNEngine {
         self.ptr as *mut ffi::NNEngine
     }
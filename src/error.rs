@@ -1,12 +1,73 @@
+use crate::{tensor::TensorType, QuantizationType};
 use deepviewrt_sys as ffi;
 use std::{ffi::CStr, fmt, io};
 
 #[derive(Debug, Clone)]
 pub enum Error {
-    NNError(&'static str),
+    NNError {
+        code: ffi::NNError,
+        desc: &'static str,
+    },
     WrapperError(String),
     Null(),
     IoError(io::ErrorKind),
+    ModelInvalid(ModelValidationError),
+    InvalidShape(String),
+    ShapeMismatch(String),
+    TypeMismatch {
+        expected: TensorType,
+        found: TensorType,
+    },
+    QuantizationMismatch {
+        expected: QuantizationType,
+        found: QuantizationType,
+    },
+}
+
+/// Reason reported by `nn_model_validate` for rejecting a model buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelValidationError {
+    code: i32,
+    reason: Option<&'static str>,
+}
+
+impl ModelValidationError {
+    pub(crate) fn from_code(code: i32) -> Self {
+        let ret = unsafe { ffi::nn_model_validate_error(code) };
+        let reason = if ret.is_null() {
+            None
+        } else {
+            unsafe { CStr::from_ptr(ret) }.to_str().ok()
+        };
+        ModelValidationError { code, reason }
+    }
+
+    /// The raw error code returned by `nn_model_validate`.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The library's description of the validation failure, if available.
+    pub fn reason(&self) -> Option<&'static str> {
+        self.reason
+    }
+}
+
+impl fmt::Display for ModelValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reason {
+            Some(reason) => write!(f, "invalid model: {}", reason),
+            None => write!(f, "invalid model: error {}", self.code),
+        }
+    }
+}
+
+impl std::error::Error for ModelValidationError {}
+
+impl From<ModelValidationError> for Error {
+    fn from(value: ModelValidationError) -> Self {
+        Error::ModelInvalid(value)
+    }
 }
 
 impl From<ffi::NNError> for Error {
@@ -17,22 +78,56 @@ impl From<ffi::NNError> for Error {
         }
         let desc = unsafe { CStr::from_ptr(ret) };
         match desc.to_str() {
-            Ok(estr) => Error::NNError(estr),
+            Ok(desc) => Error::NNError { code: value, desc },
             Err(_) => Error::Null(),
         }
     }
 }
 
+impl Error {
+    /// The closest `NNError` code for this error, used when reporting errors
+    /// back to the library.
+    pub(crate) fn code(&self) -> ffi::NNError {
+        match self {
+            Error::NNError { code, .. } => *code,
+            Error::ModelInvalid(_) => ffi::NNError_NN_ERROR_MODEL_INVALID,
+            Error::IoError(_) => ffi::NNError_NN_ERROR_SYSTEM_ERROR,
+            Error::InvalidShape(_) => ffi::NNError_NN_ERROR_INVALID_SHAPE,
+            Error::ShapeMismatch(_) => ffi::NNError_NN_ERROR_SHAPE_MISMATCH,
+            Error::TypeMismatch { .. } => ffi::NNError_NN_ERROR_TYPE_MISMATCH,
+            Error::QuantizationMismatch { .. } => ffi::NNError_NN_ERROR_INVALID_QUANT,
+            Error::WrapperError(_) | Error::Null() => ffi::NNError_NN_ERROR_INTERNAL,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::NNError(e) => write!(f, "{}", e),
+            Error::NNError { desc, .. } => write!(f, "{}", desc),
             Error::WrapperError(e) => write!(f, "{}", e),
             Error::Null() => write!(f, "null/unknown error message unavailable"),
             Error::IoError(kind) => {
                 let e = std::io::Error::from(*kind);
                 write!(f, "{}", e)
             }
+            Error::ModelInvalid(e) => write!(f, "{}", e),
+            Error::InvalidShape(e) => write!(f, "invalid shape: {}", e),
+            Error::ShapeMismatch(e) => write!(f, "shape mismatch: {}", e),
+            Error::TypeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "tensor type mismatch: expected {:?} found {:?}",
+                    expected, found
+                )
+            }
+            Error::QuantizationMismatch { expected, found } => {
+                write!(
+                    f,
+                    "quantization mismatch: expected {:?} found {:?}",
+                    expected, found
+                )
+            }
         }
     }
 }
@@ -3,13 +3,32 @@ pub mod context;
 pub mod engine;
 pub mod error;
 pub mod model;
+pub mod ops;
+pub mod profile;
+pub mod slice;
 pub mod tensor;
 use std::ffi::CStr;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QuantizationType {
     TypeNone = 0,
     TypeAffinePerTensor = 1,
     TypeAffinePerChannel = 2,
+    TypeDFP = 3,
+}
+
+impl TryFrom<u32> for QuantizationType {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<QuantizationType, Self::Error> {
+        match value {
+            0 => Ok(QuantizationType::TypeNone),
+            1 => Ok(QuantizationType::TypeAffinePerTensor),
+            2 => Ok(QuantizationType::TypeAffinePerChannel),
+            3 => Ok(QuantizationType::TypeDFP),
+            _ => Err(()),
+        }
+    }
 }
 
 pub fn version() -> &'static str {
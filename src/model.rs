@@ -1,207 +1,649 @@
-use crate::error::Error;
+use crate::{
+    error::{Error, ModelValidationError},
+    tensor::TensorType,
+};
 use deepviewrt_sys as ffi;
 use std::{
     ffi::{c_void, CStr, CString},
+    fs,
+    marker::PhantomData,
+    ops::Deref,
+    path::Path,
     slice,
 };
 
-pub fn name(model: &[u8]) -> Result<&str, Error> {
-    let ret = unsafe { ffi::nn_model_name(model.as_ptr() as *const c_void) };
-    if ret.is_null() {
-        return Err(Error::WrapperError(String::from("nn_model_name is null")));
-    }
-    let cstr = unsafe { CStr::from_ptr(ret) };
-    match cstr.to_str() {
-        Ok(s) => Ok(s),
-        Err(e) => Err(Error::WrapperError(e.to_string())),
+/// Validates the RTM buffer, returning the library's reason if the model is
+/// truncated or corrupt.
+pub fn validate(model: &[u8]) -> Result<(), ModelValidationError> {
+    let ret = unsafe { ffi::nn_model_validate(model.as_ptr() as *const c_void, model.len()) };
+    if ret != 0 {
+        return Err(ModelValidationError::from_code(ret));
     }
+    Ok(())
 }
 
-/*
-pub fn serial(&self) -> Result<&str, Error> {
+/// A DeepViewRT model (RTM) held in memory.
+///
+/// The model buffer is validated once when the model is created and the
+/// input, output and layer tables are cached so they do not need to be
+/// queried from the library on every access.
+pub struct Model {
+    buffer: Buffer,
+    inputs: Vec<u32>,
+    outputs: Vec<u32>,
+    layer_count: usize,
+}
 
+enum Buffer {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
 }
-*/
 
-pub fn label_count(model: &[u8]) -> Result<i32, Error> {
-    let ret = unsafe { ffi::nn_model_label_count(model.as_ptr() as *const c_void) };
-    if ret == 0 {
-        return Err(Error::WrapperError(String::from("Model is invalid")));
+impl Deref for Buffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Buffer::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            Buffer::Mapped(data) => data,
+        }
     }
-    Ok(ret)
 }
 
-pub fn label(model: &[u8], index: i32) -> Result<&str, Error> {
-    let ret = unsafe { ffi::nn_model_label(model.as_ptr() as *const c_void, index) };
-    if ret.is_null() {
-        return Err(Error::WrapperError(String::from("label was NULL")));
+impl Model {
+    /// Loads the model from the RTM file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        match fs::read(path) {
+            Ok(data) => Self::from_bytes(data),
+            Err(e) => Err(Error::IoError(e.kind())),
+        }
     }
-    let cstr = unsafe { CStr::from_ptr(ret) };
-    match cstr.to_str() {
-        Ok(s) => Ok(s),
-        Err(e) => Err(Error::WrapperError(e.to_string())),
+
+    /// Creates the model from an in-memory RTM buffer.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, Error> {
+        Self::new(Buffer::Owned(data))
+    }
+
+    /// Creates the model from a memory mapped RTM file.
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap(mmap: memmap2::Mmap) -> Result<Self, Error> {
+        Self::new(Buffer::Mapped(mmap))
+    }
+
+    fn new(buffer: Buffer) -> Result<Self, Error> {
+        validate(&buffer)?;
+
+        let ptr = buffer.as_ptr() as *const c_void;
+        let mut len: usize = 0;
+        let indices = unsafe { ffi::nn_model_inputs(ptr, &mut len as *mut usize) };
+        if indices.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "could not get model inputs",
+            )));
+        }
+        let inputs = unsafe { slice::from_raw_parts(indices, len) }.to_vec();
+
+        let mut len: usize = 0;
+        let indices = unsafe { ffi::nn_model_outputs(ptr, &mut len as *mut usize) };
+        if indices.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "could not get model outputs",
+            )));
+        }
+        let outputs = unsafe { slice::from_raw_parts(indices, len) }.to_vec();
+
+        let layer_count = unsafe { ffi::nn_model_layer_count(ptr) };
+
+        Ok(Model {
+            buffer,
+            inputs,
+            outputs,
+            layer_count,
+        })
+    }
+
+    /// The raw RTM buffer backing this model.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::NNModel {
+        self.buffer.as_ptr() as *const ffi::NNModel
+    }
+
+    pub fn name(&self) -> Result<&str, Error> {
+        let ret = unsafe { ffi::nn_model_name(self.as_ptr()) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from("nn_model_name is null")));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+
+    pub fn serial(&self) -> u32 {
+        unsafe { ffi::nn_model_serial(self.as_ptr()) }
+    }
+
+    pub fn uuid(&self) -> Option<&str> {
+        let ret = unsafe { ffi::nn_model_uuid(self.as_ptr()) };
+        if ret.is_null() {
+            return None;
+        }
+        unsafe { CStr::from_ptr(ret) }.to_str().ok()
+    }
+
+    /// Number of class labels in the model, zero if the model has none.
+    pub fn label_count(&self) -> usize {
+        let ret = unsafe { ffi::nn_model_label_count(self.as_ptr()) };
+        ret.max(0) as usize
+    }
+
+    pub fn label(&self, index: usize) -> Result<&str, Error> {
+        let ret = unsafe { ffi::nn_model_label(self.as_ptr(), index as i32) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(format!(
+                "label index {} out of range",
+                index
+            )));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+
+    /// Returns every class label, empty for models without labels.
+    pub fn labels(&self) -> Result<Vec<&str>, Error> {
+        (0..self.label_count())
+            .map(|index| self.label(index))
+            .collect()
+    }
+
+    /// Returns the optional icon resource for the label at `index`.
+    pub fn label_icon(&self, index: usize) -> Option<&[u8]> {
+        let mut size: usize = 0;
+        let ret = unsafe {
+            ffi::nn_model_label_icon(self.as_ptr(), index as i32, &mut size as *mut usize)
+        };
+        if ret.is_null() || size == 0 {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ret, size) })
+    }
+
+    /// Layer indices of the model inputs.
+    pub fn inputs(&self) -> &[u32] {
+        &self.inputs
+    }
+
+    /// Layer indices of the model outputs.
+    pub fn outputs(&self) -> &[u32] {
+        &self.outputs
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.layer_count
+    }
+
+    pub fn layer_name(&self, index: usize) -> Result<&str, Error> {
+        let ret = unsafe { ffi::nn_model_layer_name(self.as_ptr(), index) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "nn_model_layer_name returned null",
+            )));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+
+    pub fn layer_lookup(&self, name: &str) -> Result<i32, Error> {
+        let name = match CString::new(name) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::WrapperError(e.to_string())),
+        };
+
+        let ret = unsafe { ffi::nn_model_layer_lookup(self.as_ptr(), name.as_ptr()) };
+        if ret == -1 {
+            return Err(Error::WrapperError(String::from(
+                "Could not get index of layer",
+            )));
+        }
+        Ok(ret)
+    }
+
+    pub fn memory_size(&self) -> usize {
+        unsafe { ffi::nn_model_memory_size(self.as_ptr()) }
+    }
+
+    pub fn cache_minimum_size(&self) -> usize {
+        unsafe { ffi::nn_model_cache_minimum_size(self.as_ptr()) }
+    }
+
+    pub fn cache_optimum_size(&self) -> usize {
+        unsafe { ffi::nn_model_cache_optimum_size(self.as_ptr()) }
+    }
+
+    /// Describes each model input in order.
+    pub fn input_descriptors(&self) -> Result<Vec<IoDescriptor>, Error> {
+        self.describe(&self.inputs)
+    }
+
+    /// Describes each model output in order.
+    pub fn output_descriptors(&self) -> Result<Vec<IoDescriptor>, Error> {
+        self.describe(&self.outputs)
+    }
+
+    fn describe(&self, indices: &[u32]) -> Result<Vec<IoDescriptor>, Error> {
+        indices
+            .iter()
+            .map(|index| IoDescriptor::new(self.layer(*index as usize)?))
+            .collect()
+    }
+
+    /// Collects the model's identity and memory requirements.
+    pub fn info(&self) -> Result<ModelInfo, Error> {
+        Ok(ModelInfo {
+            name: self.name().ok().map(String::from),
+            serial: self.serial(),
+            uuid: self.uuid().map(String::from),
+            layer_count: self.layer_count,
+            memory_size: self.memory_size(),
+            cache_minimum_size: self.cache_minimum_size(),
+            cache_optimum_size: self.cache_optimum_size(),
+            inputs: self.input_descriptors()?,
+            outputs: self.output_descriptors()?,
+        })
+    }
+
+    pub fn resource_count(&self) -> usize {
+        unsafe { ffi::nn_model_resource_count(self.as_ptr()) }
+    }
+
+    /// Iterates over the resources embedded in the model.
+    pub fn resources(&self) -> impl ExactSizeIterator<Item = Result<Resource<'_>, Error>> {
+        (0..self.resource_count()).map(move |index| {
+            let ret = unsafe { ffi::nn_model_resource_at(self.as_ptr(), index) };
+            if ret.is_null() {
+                return Err(Error::WrapperError(format!(
+                    "resource index {} out of range",
+                    index
+                )));
+            }
+            unsafe { Resource::from_ptr(ret) }
+        })
+    }
+
+    /// Returns the resource with the unique `name`, or `None` if the model
+    /// does not contain it.
+    pub fn resource(&self, name: &str) -> Result<Option<Resource<'_>>, Error> {
+        let name = match CString::new(name) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::WrapperError(e.to_string())),
+        };
+        let ret = unsafe { ffi::nn_model_resource(self.as_ptr(), name.as_ptr()) };
+        if ret.is_null() {
+            return Ok(None);
+        }
+        unsafe { Resource::from_ptr(ret) }.map(Some)
+    }
+
+    /// Returns a view of the layer at `index`.
+    pub fn layer(&self, index: usize) -> Result<Layer<'_>, Error> {
+        if index >= self.layer_count {
+            return Err(Error::WrapperError(format!(
+                "layer index {} out of range",
+                index
+            )));
+        }
+        Ok(Layer { model: self, index })
     }
-}
 
-pub fn inputs(model: &[u8]) -> Result<Vec<u32>, Error> {
-    let mut len: usize = 0;
-    let indices =
-        unsafe { ffi::nn_model_inputs(model.as_ptr() as *const c_void, &mut len as *mut usize) };
-    if indices.is_null() {
-        return Err(Error::WrapperError(String::from(
-            "could not get model inputs",
-        )));
+    /// Iterates over every layer of the model in graph order.
+    pub fn layers(&self) -> impl ExactSizeIterator<Item = Layer<'_>> {
+        (0..self.layer_count).map(move |index| Layer { model: self, index })
     }
-    Ok(unsafe { slice::from_raw_parts(indices, len) }.to_vec())
 }
 
-pub fn outputs(model: &[u8]) -> Result<Vec<u32>, Error> {
-    let mut len: usize = 0;
+/// Identity and memory requirements of a model.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelInfo {
+    pub name: Option<String>,
+    pub serial: u32,
+    pub uuid: Option<String>,
+    pub layer_count: usize,
+    pub memory_size: usize,
+    pub cache_minimum_size: usize,
+    pub cache_optimum_size: usize,
+    pub inputs: Vec<IoDescriptor>,
+    pub outputs: Vec<IoDescriptor>,
+}
 
-    let indices =
-        unsafe { ffi::nn_model_outputs(model.as_ptr() as *const c_void, &mut len as *mut usize) };
-    if indices.is_null() {
-        return Err(Error::WrapperError(String::from(
-            "could not get model outputs",
-        )));
+/// Describes a model input or output layer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IoDescriptor {
+    /// The layer index within the model.
+    pub index: usize,
+    pub name: String,
+    pub shape: Vec<i32>,
+    pub datatype: TensorType,
+    /// Quantization scales, empty when the layer is not quantized.
+    pub scales: Vec<f32>,
+    /// Quantization zero-points, empty when the layer is not quantized.
+    pub zeros: Vec<i32>,
+    /// The channel axis for per-channel quantization.
+    pub axis: Option<i32>,
+}
+
+impl IoDescriptor {
+    fn new(layer: Layer<'_>) -> Result<Self, Error> {
+        Ok(IoDescriptor {
+            index: layer.index(),
+            name: layer.name()?.to_string(),
+            shape: layer.shape().to_vec(),
+            datatype: layer.datatype()?,
+            scales: layer.scales().to_vec(),
+            zeros: layer.zeros().to_vec(),
+            axis: layer.axis(),
+        })
     }
-    Ok(unsafe { slice::from_raw_parts(indices, len) }.to_vec())
 }
 
-pub fn layer_count(model: &[u8]) -> usize {
-    unsafe { ffi::nn_model_layer_count(model.as_ptr() as *const c_void) }
+/// A borrowed view of a single layer within a [`Model`].
+#[derive(Clone, Copy)]
+pub struct Layer<'a> {
+    model: &'a Model,
+    index: usize,
 }
 
-pub fn layer_name(model: &[u8], index: usize) -> Result<&str, Error> {
-    let ret = unsafe { ffi::nn_model_layer_name(model.as_ptr() as *const c_void, index) };
-    if ret.is_null() {
-        return Err(Error::WrapperError(String::from(
-            "nn_model_layer_name returned null",
-        )));
+impl<'a> Layer<'a> {
+    pub fn index(&self) -> usize {
+        self.index
     }
-    let cstr = unsafe { CStr::from_ptr(ret) };
-    match cstr.to_str() {
-        Ok(s) => Ok(s),
-        Err(e) => Err(Error::WrapperError(e.to_string())),
+
+    pub fn name(&self) -> Result<&'a str, Error> {
+        self.model.layer_name(self.index)
+    }
+
+    /// The layer's operation type, such as "conv" or "dense".
+    pub fn layer_type(&self) -> Result<&'a str, Error> {
+        let ret = unsafe { ffi::nn_model_layer_type(self.model.as_ptr(), self.index) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "nn_model_layer_type returned null",
+            )));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+
+    pub fn layer_type_id(&self) -> i16 {
+        unsafe { ffi::nn_model_layer_type_id(self.model.as_ptr(), self.index) }
+    }
+
+    /// The name of the layer's datatype as stored in the model.
+    pub fn datatype_name(&self) -> Result<&'a str, Error> {
+        let ret = unsafe { ffi::nn_model_layer_datatype(self.model.as_ptr(), self.index) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(String::from(
+                "nn_model_layer_datatype returned null",
+            )));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+
+    pub fn datatype(&self) -> Result<TensorType, Error> {
+        let ret = unsafe { ffi::nn_model_layer_datatype_id(self.model.as_ptr(), self.index) };
+        match TensorType::try_from(ret) {
+            Ok(tensor_type) => Ok(tensor_type),
+            Err(_) => Err(Error::WrapperError(format!(
+                "invalid datatype {} for layer {}",
+                ret, self.index
+            ))),
+        }
+    }
+
+    /// The layer's output shape, empty if the model does not define one.
+    pub fn shape(&self) -> &'a [i32] {
+        let mut n_dims: usize = 0;
+        let ret = unsafe {
+            ffi::nn_model_layer_shape(self.model.as_ptr(), self.index, &mut n_dims as *mut usize)
+        };
+        if ret.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(ret, n_dims) }
+    }
+
+    /// Quantization zero-points, either empty, a single per-tensor value or
+    /// one per channel along [`Layer::axis`].
+    pub fn zeros(&self) -> &'a [i32] {
+        let mut n_zeros: usize = 0;
+        let ret = unsafe {
+            ffi::nn_model_layer_zeros(self.model.as_ptr(), self.index, &mut n_zeros as *mut usize)
+        };
+        if ret.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(ret, n_zeros) }
+    }
+
+    /// Quantization scales, either empty, a single per-tensor value or one per
+    /// channel along [`Layer::axis`].
+    pub fn scales(&self) -> &'a [f32] {
+        let mut n_scales: usize = 0;
+        let ret = unsafe {
+            ffi::nn_model_layer_scales(self.model.as_ptr(), self.index, &mut n_scales as *mut usize)
+        };
+        if ret.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(ret, n_scales) }
+    }
+
+    /// The natural data (channel) axis of the layer, if one is set.
+    pub fn axis(&self) -> Option<i32> {
+        let ret = unsafe { ffi::nn_model_layer_axis(self.model.as_ptr(), self.index) };
+        if ret < 0 {
+            return None;
+        }
+        Some(ret)
+    }
+
+    /// Indices of the layers which feed into this layer.
+    pub fn inputs(&self) -> &'a [u32] {
+        let mut inputs: *const u32 = std::ptr::null();
+        let len = unsafe {
+            ffi::nn_model_layer_inputs(
+                self.model.as_ptr(),
+                self.index,
+                &mut inputs as *mut *const u32,
+            )
+        };
+        if inputs.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(inputs, len) }
     }
+
+    /// Returns the layer parameter named `key`, such as "weights", "bias" or
+    /// "padding", or `None` if the layer does not define it.
+    pub fn parameter(&self, key: &str) -> Result<Option<LayerParameter<'a>>, Error> {
+        let key = match CString::new(key) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::WrapperError(e.to_string())),
+        };
+        let ret =
+            unsafe { ffi::nn_model_layer_parameter(self.model.as_ptr(), self.index, key.as_ptr()) };
+        if ret.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(unsafe { LayerParameter::from_ptr(ret) }))
+    }
+}
+
+/// A layer parameter whose data is borrowed directly from the model buffer.
+///
+/// The typed accessors return `None` when the parameter does not hold data of
+/// the requested type.
+#[derive(Clone, Copy)]
+pub struct LayerParameter<'a> {
+    ptr: *const ffi::NNModelParameter,
+    _model: PhantomData<&'a Model>,
 }
 
-pub fn layer_lookup(model: &[u8], name: &str) -> Result<i32, Error> {
-    let name = match CString::new(name) {
-        Ok(s) => s,
-        Err(e) => return Err(Error::WrapperError(e.to_string())),
-    };
+impl<'a> LayerParameter<'a> {
+    /// # Safety
+    /// The pointer must reference a parameter within a model buffer which
+    /// outlives `'a`.
+    pub(crate) unsafe fn from_ptr(ptr: *const ffi::NNModelParameter) -> Self {
+        LayerParameter {
+            ptr,
+            _model: PhantomData,
+        }
+    }
+
+    /// The parameter's shape, empty if the model does not define one.
+    pub fn shape(&self) -> &'a [i32] {
+        let mut n_dims: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_shape(self.ptr, &mut n_dims as *mut usize) };
+        if ret.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(ret, n_dims) }
+    }
+
+    pub fn data_f32(&self) -> Option<&'a [f32]> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_f32(self.ptr, &mut len as *mut usize) };
+        if ret.is_null() {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ret, len) })
+    }
+
+    pub fn data_i32(&self) -> Option<&'a [i32]> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_i32(self.ptr, &mut len as *mut usize) };
+        if ret.is_null() {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ret, len) })
+    }
+
+    pub fn data_i16(&self) -> Option<&'a [i16]> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_i16(self.ptr, &mut len as *mut usize) };
+        if ret.is_null() {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ret, len) })
+    }
+
+    pub fn data_i8(&self) -> Option<&'a [i8]> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_i8(self.ptr, &mut len as *mut usize) };
+        if ret.is_null() {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ret, len) })
+    }
+
+    pub fn data_raw(&self) -> Option<&'a [u8]> {
+        let mut len: usize = 0;
+        let ret = unsafe { ffi::nn_model_parameter_data_raw(self.ptr, &mut len as *mut usize) };
+        if ret.is_null() {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(ret, len) })
+    }
+
+    /// Number of entries in the parameter's string list.
+    pub fn string_count(&self) -> usize {
+        unsafe { ffi::nn_model_parameter_data_str_len(self.ptr) }
+    }
+
+    pub fn string(&self, index: usize) -> Result<&'a str, Error> {
+        let ret = unsafe { ffi::nn_model_parameter_data_str(self.ptr, index) };
+        if ret.is_null() {
+            return Err(Error::WrapperError(format!(
+                "parameter string index {} out of range",
+                index
+            )));
+        }
+        let cstr = unsafe { CStr::from_ptr(ret) };
+        match cstr.to_str() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
 
-    let ret =
-        unsafe { ffi::nn_model_layer_lookup(model.as_ptr() as *const c_void, name.into_raw()) };
-    if ret == -1 {
-        return Err(Error::WrapperError(String::from(
-            "Could not get index of layer",
-        )));
+    /// Iterates over the parameter's string list.
+    pub fn strings(&self) -> impl ExactSizeIterator<Item = Result<&'a str, Error>> + 'a {
+        let param = *self;
+        (0..self.string_count()).map(move |index| param.string(index))
     }
-    Ok(ret)
 }
 
-pub fn memory_size(model: &[u8]) -> usize {
-    unsafe { ffi::nn_model_memory_size(model.as_ptr() as *const c_void) }
+/// A resource embedded in the model such as a labels file, anchor table or
+/// configuration blob.
+#[derive(Debug, Clone, Copy)]
+pub struct Resource<'a> {
+    pub name: &'a str,
+    pub mime: Option<&'a str>,
+    pub meta: Option<&'a str>,
+    pub data: &'a [u8],
 }
 
-// pub fn layer_type(&self, index: usize) -> Result<&str, Error> {
-//     let ret = unsafe { ffi::nn_model_layer_type(self.ptr, index) };
-//     if ret.is_null() {
-//         return Err(Error::WrapperError(String::from("index out of range")));
-//     }
-
-//     let cstr = unsafe { CStr::from_ptr(ret) };
-//     match cstr.to_str() {
-//         Ok(s) => Ok(s),
-//         Err(e) => Err(Error::WrapperError(e.to_string())),
-//     }
-// }
-
-// pub fn layer_type_id(&self, index: usize) -> Result<i16, Error> {
-//     let ret = unsafe { ffi::nn_model_layer_type_id(self.ptr, index) };
-//     if ret == 0 {
-//         return Err(Error::WrapperError(String::from("index out of range")));
-//     }
-
-//     return Ok(ret);
-// }
-
-// pub fn layer_datatype(&self, index: usize) -> Result<&str, Error> {
-//     let ret = unsafe { ffi::nn_model_layer_datatype(self.ptr, index) };
-//     if ret.is_null() {
-//         return Err(Error::WrapperError(String::from("index out of range")));
-//     }
-
-//     let cstr = unsafe { CStr::from_ptr(ret) };
-//     match cstr.to_str() {
-//         Ok(s) => Ok(s),
-//         Err(e) => Err(Error::WrapperError(e.to_string())),
-//     }
-// }
-
-// pub fn layer_datatype_id(&self, index: usize) -> Result<TensorType, Error> {
-//     let ret = unsafe { ffi::nn_model_layer_datatype_id(self.ptr, index) };
-//     if ret == 0 {
-//         return Err(Error::WrapperError(String::from(
-//             "Model is invalid or Index is out of range",
-//         )));
-//     }
-//     match TensorType::try_from(ret as u32) {
-//         Ok(tensor_type) => {
-//             return Ok(tensor_type);
-//         }
-//         Err(_) => {
-//             return Err(Error::WrapperError(String::from("Not a valid
-// TensorType")));         }
-//     }
-// }
-
-// pub fn layer_zeros(&self, index: usize) -> Result<&[i32], Error> {
-//     let mut n_zeros: isize = -1;
-//     let ret = unsafe {
-//         ffi::nn_model_layer_zeros(self.ptr, index, &mut n_zeros as *mut isize
-// as *mut usize)     };
-//     if ret.is_null() || n_zeros == -1 {
-//         return Err(Error::WrapperError(String::from("Index out of range")));
-//     }
-//     return unsafe { Ok(std::slice::from_raw_parts(ret, n_zeros as usize)) };
-// }
-
-// pub fn layer_scales(&self, index: usize) -> Result<&[f32], Error> {
-//     let mut n_scales: isize = -1;
-//     let ret = unsafe {
-//         ffi::nn_model_layer_scales(self.ptr, index, &mut n_scales as *mut
-// isize as *mut usize)     };
-//     if ret.is_null() || n_scales == -1 {
-//         return Err(Error::WrapperError(String::from("Index out of range")));
-//     }
-//     return unsafe { Ok(std::slice::from_raw_parts(ret, n_scales as usize)) };
-// }
-
-// pub fn layer_axis(&self, index: usize) -> Result<i32, Error> {
-//     let ret = unsafe { ffi::nn_model_layer_axis(self.ptr, index) };
-//     if ret == 0 {
-//         //TODO 0 indicates faliure, but docs say -1 (should be clarified
-//         return Err(Error::WrapperError(String::from("Index out of range")));
-//     }
-//     return Ok(ret);
-// }
-
-// pub fn layer_shape(&self, index: usize) -> Result<&[i32], Error> {
-//     let n_dims: isize = -1;
-//     let ret = unsafe { ffi::nn_model_layer_shape(self.ptr, index, &mut
-// (n_dims as usize)) };     if ret.is_null() || n_dims == -1 {
-//         return Err(Error::WrapperError(String::from("Index out of range")));
-//     }
-//     return unsafe { Ok(std::slice::from_raw_parts(ret, n_dims as usize)) };
-// }
-
-/*
-pub fn layer_inputs(&self, index: usize) {}
-
-pub fn layer_parameter() {}
-
-pub fn layer_parameter_shape() {}
-*/
+impl<'a> Resource<'a> {
+    unsafe fn from_ptr(ptr: *const ffi::NNModelResource) -> Result<Self, Error> {
+        let name = match optional_str(ffi::nn_model_resource_name(ptr))? {
+            Some(name) => name,
+            None => return Err(Error::WrapperError(String::from("resource has no name"))),
+        };
+        let mime = optional_str(ffi::nn_model_resource_mime(ptr))?;
+        let meta = optional_str(ffi::nn_model_resource_meta(ptr))?;
+
+        let mut len: usize = 0;
+        let ret = ffi::nn_model_resource_data(ptr, &mut len as *mut usize);
+        let data = if ret.is_null() {
+            &[]
+        } else {
+            slice::from_raw_parts(ret, len)
+        };
+
+        Ok(Resource {
+            name,
+            mime,
+            meta,
+            data,
+        })
+    }
+}
+
+unsafe fn optional_str<'a>(ptr: *const std::os::raw::c_char) -> Result<Option<&'a str>, Error> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    match CStr::from_ptr(ptr).to_str() {
+        Ok(s) => Ok(Some(s)),
+        Err(e) => Err(Error::WrapperError(e.to_string())),
+    }
+}
@@ -1,22 +1,26 @@
-use std::fs;
-
-use deepviewrt::model;
+use deepviewrt::model::Model;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let rtm = fs::read("model.rtm")?;
-    println!("model name: {}", model::name(&rtm)?);
-    println!("model inputs: {:?}", model::inputs(&rtm)?);
-    println!("model outputs: {:?}", model::outputs(&rtm)?);
-    println!("model layer_count: {}", model::layer_count(&rtm));
+    let model = Model::from_file("model.rtm")?;
+    println!("model name: {}", model.name()?);
+    println!("model inputs: {:?}", model.inputs());
+    println!("model outputs: {:?}", model.outputs());
+    println!("model layer_count: {}", model.layer_count());
 
-    for i in 0..model::layer_count(&rtm) {
-        println!("model layer {} name: {}", i, model::layer_name(&rtm, i)?);
-        // println!("model layer {} type: {}", i, model::layer_type(&rtm, i)?);
+    for layer in model.layers() {
+        println!(
+            "model layer {} name: {} type: {} datatype: {:?} shape: {:?}",
+            layer.index(),
+            layer.name()?,
+            layer.layer_type()?,
+            layer.datatype()?,
+            layer.shape()
+        );
     }
 
-    let input_name = model::layer_name(&rtm, model::inputs(&rtm)?[0] as usize)?;
-    let input_index = model::layer_lookup(&rtm, input_name)?;
-    assert_eq!(input_index, model::inputs(&rtm)?[0] as i32);
+    let input_name = model.layer_name(model.inputs()[0] as usize)?;
+    let input_index = model.layer_lookup(input_name)?;
+    assert_eq!(input_index, model.inputs()[0] as i32);
 
     Ok(())
 }
@@ -0,0 +1,221 @@
+use crate::{
+    error::Error,
+    model::{Layer, LayerParameter, Model},
+    tensor::{Tensor, TensorMut},
+};
+use deepviewrt_sys as ffi;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    ffi::CStr,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    sync::Mutex,
+};
+
+type OpFn = dyn Fn(&mut OpContext<'_>) -> Result<(), Error>;
+
+/// Custom operators registered on a context, keyed by op name.
+#[derive(Default)]
+pub(crate) struct OpRegistry {
+    ops: HashMap<String, Box<OpFn>>,
+    binding: Cell<Option<Binding>>,
+}
+
+/// The context's model and tensor table, lent to the ops while the library
+/// runs the context.
+#[derive(Clone, Copy)]
+struct Binding {
+    model: Option<*const Model>,
+    tensors: *mut [Option<Tensor>],
+}
+
+impl OpRegistry {
+    pub(crate) fn insert<F>(&mut self, name: &str, op: F)
+    where
+        F: Fn(&mut OpContext<'_>) -> Result<(), Error> + 'static,
+    {
+        self.ops.insert(name.to_string(), Box::new(op));
+    }
+
+    /// Lends the model and tensor table to the ops for the duration of `f`,
+    /// which must be the library call that may invoke them.
+    pub(crate) fn bind<T>(
+        &self,
+        model: Option<&Model>,
+        tensors: &mut [Option<Tensor>],
+        f: impl FnOnce() -> T,
+    ) -> T {
+        self.binding.set(Some(Binding {
+            model: model.map(|model| model as *const Model),
+            tensors: tensors as *mut [Option<Tensor>],
+        }));
+        let ret = f();
+        self.binding.set(None);
+        ret
+    }
+}
+
+/// Maps each context with registered ops to its registry.  The library's
+/// callback carries no user data so the trampoline finds the registry
+/// through the context pointer.
+static REGISTRIES: Mutex<Vec<(usize, usize)>> = Mutex::new(Vec::new());
+
+/// Installs the trampoline on the context and associates it with `registry`,
+/// which must stay at a stable address until [`unregister`] is called.
+pub(crate) fn register(context: *mut ffi::NNContext, registry: &OpRegistry) -> Result<(), Error> {
+    {
+        let mut registries = REGISTRIES.lock().unwrap_or_else(|e| e.into_inner());
+        registries.retain(|(ctx, _)| *ctx != context as usize);
+        registries.push((context as usize, registry as *const OpRegistry as usize));
+    }
+    let ret = unsafe { ffi::nn_context_user_ops_register(context, Some(trampoline)) };
+    if ret != ffi::NNError_NN_SUCCESS {
+        unregister(context);
+        return Err(Error::from(ret));
+    }
+    Ok(())
+}
+
+pub(crate) fn unregister(context: *mut ffi::NNContext) {
+    let mut registries = REGISTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    registries.retain(|(ctx, _)| *ctx != context as usize);
+}
+
+unsafe extern "C" fn trampoline(
+    context: *mut ffi::NNContext,
+    opname: *const c_char,
+    index: usize,
+) -> ffi::NNError {
+    let registry = {
+        let registries = REGISTRIES.lock().unwrap_or_else(|e| e.into_inner());
+        match registries.iter().find(|(ctx, _)| *ctx == context as usize) {
+            Some((_, registry)) => *registry as *const OpRegistry,
+            None => return ffi::NNError_NN_ERROR_KERNEL_MISSING,
+        }
+    };
+    if opname.is_null() {
+        return ffi::NNError_NN_ERROR_KERNEL_MISSING;
+    }
+    let name = match CStr::from_ptr(opname).to_str() {
+        Ok(name) => name,
+        Err(_) => return ffi::NNError_NN_ERROR_KERNEL_MISSING,
+    };
+    let op = match (*registry).ops.get(name) {
+        Some(op) => op,
+        None => return ffi::NNError_NN_ERROR_KERNEL_MISSING,
+    };
+    let binding = match (*registry).binding.get() {
+        Some(binding) => binding,
+        None => return ffi::NNError_NN_ERROR_INTERNAL,
+    };
+
+    let ret = panic::catch_unwind(AssertUnwindSafe(|| {
+        let model = match binding.model {
+            Some(model) => &*model,
+            None => return Err(Error::WrapperError(String::from("no model loaded"))),
+        };
+        let mut op_context = OpContext::new(model, &mut *binding.tensors, name, index)?;
+        op(&mut op_context)
+    }));
+    match ret {
+        Ok(Ok(())) => ffi::NNError_NN_SUCCESS,
+        Ok(Err(e)) => e.code(),
+        Err(_) => ffi::NNError_NN_ERROR_INTERNAL,
+    }
+}
+
+/// The layer being evaluated by a custom operator registered through
+/// [`Context::register_op`](crate::context::Context::register_op).  Inputs
+/// and output borrow the context's own tensors.
+pub struct OpContext<'c> {
+    name: &'c str,
+    layer: Layer<'c>,
+    tensors: &'c [Option<Tensor>],
+    output: &'c mut Tensor,
+}
+
+impl<'c> OpContext<'c> {
+    fn new(
+        model: &'c Model,
+        tensors: &'c mut [Option<Tensor>],
+        name: &'c str,
+        index: usize,
+    ) -> Result<Self, Error> {
+        let layer = model.layer(index)?;
+        if index >= tensors.len() {
+            return Err(Error::WrapperError(format!("No tensor found at {}", index)));
+        }
+        // Inputs precede the layer in graph order, splitting the table there
+        // lets them be borrowed alongside the output.
+        let (tensors, rest) = tensors.split_at_mut(index);
+        for input in layer.inputs() {
+            match tensors.get(*input as usize) {
+                Some(Some(_)) => {}
+                _ => {
+                    return Err(Error::WrapperError(format!(
+                        "op input {} is not available to layer {}",
+                        input, index
+                    )))
+                }
+            }
+        }
+        let output = match rest.first_mut() {
+            Some(Some(tensor)) => tensor,
+            _ => return Err(Error::WrapperError(format!("No tensor found at {}", index))),
+        };
+
+        Ok(OpContext {
+            name,
+            layer,
+            tensors,
+            output,
+        })
+    }
+
+    /// The op name as stored in the model.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// The index of the layer being evaluated.
+    pub fn index(&self) -> usize {
+        self.layer.index()
+    }
+
+    pub fn input_count(&self) -> usize {
+        self.layer.inputs().len()
+    }
+
+    pub fn inputs(&self) -> impl ExactSizeIterator<Item = &'c Tensor> + 'c {
+        let tensors = self.tensors;
+        self.layer
+            .inputs()
+            .iter()
+            .map(move |input| tensors[*input as usize].as_ref().unwrap())
+    }
+
+    pub fn input(&self, index: usize) -> Result<&'c Tensor, Error> {
+        match self.layer.inputs().get(index) {
+            Some(input) => Ok(self.tensors[*input as usize].as_ref().unwrap()),
+            None => Err(Error::WrapperError(format!(
+                "op input {} out of range",
+                index
+            ))),
+        }
+    }
+
+    pub fn output(&self) -> &Tensor {
+        self.output
+    }
+
+    pub fn output_mut(&mut self) -> TensorMut<'_> {
+        TensorMut::from(&mut *self.output)
+    }
+
+    /// Returns the layer parameter named `key`, or `None` if the layer does not
+    /// define it.
+    pub fn parameter(&self, key: &str) -> Result<Option<LayerParameter<'c>>, Error> {
+        self.layer.parameter(key)
+    }
+}
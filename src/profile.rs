@@ -0,0 +1,197 @@
+use crate::error::Error;
+use std::{cmp::Reverse, fmt::Write, io};
+
+/// Timing of a single layer as recorded by its output tensor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerProfile {
+    pub index: usize,
+    pub name: String,
+    pub layer_type: String,
+    /// Nanoseconds spent computing the layer.
+    pub compute_ns: i64,
+    /// Nanoseconds spent mapping and unmapping the layer's output.
+    pub io_ns: i64,
+    pub shape: Vec<i32>,
+}
+
+impl LayerProfile {
+    pub fn total_ns(&self) -> i64 {
+        self.compute_ns + self.io_ns
+    }
+}
+
+/// Per-layer profile of a model run, collected through
+/// [`Context::profile`](crate::context::Context::profile).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Profile {
+    pub layers: Vec<LayerProfile>,
+}
+
+impl Profile {
+    pub fn compute_ns(&self) -> i64 {
+        self.layers.iter().map(|layer| layer.compute_ns).sum()
+    }
+
+    pub fn io_ns(&self) -> i64 {
+        self.layers.iter().map(|layer| layer.io_ns).sum()
+    }
+
+    pub fn total_ns(&self) -> i64 {
+        self.compute_ns() + self.io_ns()
+    }
+
+    /// Layers ordered from the slowest to the fastest by compute and I/O time.
+    pub fn slowest(&self) -> Vec<&LayerProfile> {
+        let mut layers: Vec<&LayerProfile> = self.layers.iter().collect();
+        layers.sort_by_key(|layer| Reverse(layer.total_ns()));
+        layers
+    }
+
+    /// Total compute and I/O time for each layer type, slowest first.
+    pub fn by_type(&self) -> Vec<(&str, i64)> {
+        let mut types: Vec<(&str, i64)> = Vec::new();
+        for layer in &self.layers {
+            match types.iter_mut().find(|(t, _)| *t == layer.layer_type) {
+                Some((_, ns)) => *ns += layer.total_ns(),
+                None => types.push((&layer.layer_type, layer.total_ns())),
+            }
+        }
+        types.sort_by_key(|(_, ns)| Reverse(*ns));
+        types
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("index,name,type,compute_ns,io_ns,shape\n");
+        for layer in &self.layers {
+            let shape: Vec<String> = layer.shape.iter().map(|d| d.to_string()).collect();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                layer.index,
+                csv_field(&layer.name),
+                csv_field(&layer.layer_type),
+                layer.compute_ns,
+                layer.io_ns,
+                shape.join("x")
+            );
+        }
+        csv
+    }
+
+    pub fn write_csv<W: io::Write>(&self, mut writer: W) -> Result<(), Error> {
+        match writer.write_all(self.to_csv().as_bytes()) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::IoError(e.kind())),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, Error> {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(Error::WrapperError(e.to_string())),
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(
+        index: usize,
+        name: &str,
+        layer_type: &str,
+        compute_ns: i64,
+        io_ns: i64,
+    ) -> LayerProfile {
+        LayerProfile {
+            index,
+            name: name.to_string(),
+            layer_type: layer_type.to_string(),
+            compute_ns,
+            io_ns,
+            shape: vec![1, 8, 8, 3],
+        }
+    }
+
+    fn profile() -> Profile {
+        Profile {
+            layers: vec![
+                layer(0, "input", "input", 0, 10),
+                layer(1, "conv_1", "conv", 300, 20),
+                layer(2, "relu", "relu", 50, 5),
+                layer(3, "conv_2", "conv", 400, 30),
+            ],
+        }
+    }
+
+    #[test]
+    fn totals() {
+        let profile = profile();
+        assert_eq!(profile.compute_ns(), 750);
+        assert_eq!(profile.io_ns(), 65);
+        assert_eq!(profile.total_ns(), 815);
+        assert_eq!(profile.layers[1].total_ns(), 320);
+    }
+
+    #[test]
+    fn slowest() {
+        let profile = profile();
+        let order: Vec<usize> = profile.slowest().iter().map(|l| l.index).collect();
+        assert_eq!(order, [3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn slowest_keeps_ties_in_graph_order() {
+        let profile = Profile {
+            layers: vec![layer(0, "a", "add", 5, 0), layer(1, "b", "add", 5, 0)],
+        };
+        let order: Vec<usize> = profile.slowest().iter().map(|l| l.index).collect();
+        assert_eq!(order, [0, 1]);
+    }
+
+    #[test]
+    fn by_type() {
+        let profile = profile();
+        assert_eq!(
+            profile.by_type(),
+            [("conv", 750), ("relu", 55), ("input", 10)]
+        );
+        assert!(Profile::default().by_type().is_empty());
+    }
+
+    #[test]
+    fn to_csv() {
+        let profile = Profile {
+            layers: vec![layer(0, "conv_1", "conv", 300, 20)],
+        };
+        assert_eq!(
+            profile.to_csv(),
+            "index,name,type,compute_ns,io_ns,shape\n0,conv_1,conv,300,20,1x8x8x3\n"
+        );
+
+        let mut out = Vec::new();
+        profile.write_csv(&mut out).unwrap();
+        assert_eq!(out, profile.to_csv().as_bytes());
+    }
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field(""), "");
+    }
+}
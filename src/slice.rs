@@ -0,0 +1,218 @@
+use crate::error::Error;
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+/// A range along one tensor dimension used by
+/// [`Tensor::slice_ranges`](crate::tensor::Tensor::slice_ranges).  Negative
+/// bounds count back from the end of the dimension.  Ranges are usually built
+/// with the [`s!`](crate::s) macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+    /// Whether `end` is included in the range.
+    pub inclusive: bool,
+    pub step: i32,
+}
+
+impl SliceRange {
+    pub fn step(self, step: i32) -> Self {
+        SliceRange { step, ..self }
+    }
+
+    /// Resolves the range against a dimension of length `len`, returning the
+    /// head, tail and stride.
+    pub(crate) fn resolve(&self, len: i32) -> Result<(i32, i32, i32), Error> {
+        let bound = |value: i32| if value < 0 { len + value } else { value };
+        let head = self.start.map_or(0, bound);
+        let tail = match self.end {
+            Some(end) if self.inclusive => bound(end).checked_add(1),
+            Some(end) => Some(bound(end)),
+            None => Some(len),
+        };
+        match tail {
+            Some(tail) if self.step > 0 && head >= 0 && tail <= len && head < tail => {
+                Ok((head, tail, self.step))
+            }
+            _ => Err(Error::InvalidShape(format!(
+                "slice {:?} is out of bounds for dimension of length {}",
+                self, len
+            ))),
+        }
+    }
+}
+
+impl From<RangeFull> for SliceRange {
+    fn from(_: RangeFull) -> Self {
+        SliceRange {
+            start: None,
+            end: None,
+            inclusive: false,
+            step: 1,
+        }
+    }
+}
+
+impl From<Range<i32>> for SliceRange {
+    fn from(range: Range<i32>) -> Self {
+        SliceRange {
+            start: Some(range.start),
+            end: Some(range.end),
+            inclusive: false,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeInclusive<i32>> for SliceRange {
+    fn from(range: RangeInclusive<i32>) -> Self {
+        SliceRange {
+            start: Some(*range.start()),
+            end: Some(*range.end()),
+            inclusive: true,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeFrom<i32>> for SliceRange {
+    fn from(range: RangeFrom<i32>) -> Self {
+        SliceRange {
+            start: Some(range.start),
+            end: None,
+            inclusive: false,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeTo<i32>> for SliceRange {
+    fn from(range: RangeTo<i32>) -> Self {
+        SliceRange {
+            start: None,
+            end: Some(range.end),
+            inclusive: false,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeToInclusive<i32>> for SliceRange {
+    fn from(range: RangeToInclusive<i32>) -> Self {
+        SliceRange {
+            start: None,
+            end: Some(range.end),
+            inclusive: true,
+            step: 1,
+        }
+    }
+}
+
+impl From<i32> for SliceRange {
+    /// Selects a single index while keeping the dimension.
+    fn from(index: i32) -> Self {
+        SliceRange {
+            start: Some(index),
+            end: Some(index),
+            inclusive: true,
+            step: 1,
+        }
+    }
+}
+
+/// Builds an array of [`SliceRange`] using range syntax, one per dimension,
+/// with an optional step after a semicolon.
+///
+/// ```ignore
+/// let crop = tensor.slice_ranges(&s![.., 10..20, 0..64;2, ..])?;
+/// ```
+#[macro_export]
+macro_rules! s {
+    ($($range:expr $(; $step:expr)?),* $(,)?) => {
+        [$($crate::slice::SliceRange::from($range)$(.step($step))?),*]
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_ranges() {
+        let range = |start, end, inclusive| SliceRange {
+            start,
+            end,
+            inclusive,
+            step: 1,
+        };
+        assert_eq!(SliceRange::from(..), range(None, None, false));
+        assert_eq!(SliceRange::from(2..5), range(Some(2), Some(5), false));
+        assert_eq!(SliceRange::from(2..=5), range(Some(2), Some(5), true));
+        assert_eq!(SliceRange::from(2..), range(Some(2), None, false));
+        assert_eq!(SliceRange::from(..5), range(None, Some(5), false));
+        assert_eq!(SliceRange::from(..=5), range(None, Some(5), true));
+        assert_eq!(SliceRange::from(3), range(Some(3), Some(3), true));
+        assert_eq!(SliceRange::from(1..4).step(2).step, 2);
+    }
+
+    #[test]
+    fn resolve() {
+        assert_eq!(SliceRange::from(..).resolve(10).unwrap(), (0, 10, 1));
+        assert_eq!(SliceRange::from(2..5).resolve(10).unwrap(), (2, 5, 1));
+        assert_eq!(SliceRange::from(2..=5).resolve(10).unwrap(), (2, 6, 1));
+        assert_eq!(SliceRange::from(..=9).resolve(10).unwrap(), (0, 10, 1));
+        assert_eq!(SliceRange::from(3).resolve(10).unwrap(), (3, 4, 1));
+        assert_eq!(
+            SliceRange::from(0..10).step(3).resolve(10).unwrap(),
+            (0, 10, 3)
+        );
+    }
+
+    #[test]
+    fn resolve_negative() {
+        assert_eq!(SliceRange::from(-3..).resolve(10).unwrap(), (7, 10, 1));
+        assert_eq!(SliceRange::from(..-1).resolve(10).unwrap(), (0, 9, 1));
+        assert_eq!(SliceRange::from(-4..-2).resolve(10).unwrap(), (6, 8, 1));
+        assert_eq!(SliceRange::from(..=-1).resolve(10).unwrap(), (0, 10, 1));
+        assert_eq!(SliceRange::from(-2..=-1).resolve(10).unwrap(), (8, 10, 1));
+        assert_eq!(SliceRange::from(-1).resolve(10).unwrap(), (9, 10, 1));
+    }
+
+    #[test]
+    fn resolve_out_of_bounds() {
+        assert!(SliceRange::from(0..11).resolve(10).is_err());
+        assert!(SliceRange::from(..=10).resolve(10).is_err());
+        assert!(SliceRange::from(-11..).resolve(10).is_err());
+        assert!(SliceRange::from(10).resolve(10).is_err());
+        assert!(SliceRange::from(-11).resolve(10).is_err());
+        assert!(SliceRange::from(5..5).resolve(10).is_err());
+        assert!(SliceRange::from(-2..5).resolve(10).is_err());
+        assert!(SliceRange::from(..).step(0).resolve(10).is_err());
+        assert!(SliceRange::from(..).step(-1).resolve(10).is_err());
+    }
+
+    #[test]
+    fn resolve_inclusive_max() {
+        assert!(SliceRange::from(0..=i32::MAX).resolve(10).is_err());
+        assert!(SliceRange::from(..=i32::MAX).resolve(i32::MAX).is_err());
+        assert!(SliceRange::from(i32::MAX).resolve(i32::MAX).is_err());
+        assert_eq!(
+            SliceRange::from(..=i32::MAX - 1).resolve(i32::MAX).unwrap(),
+            (0, i32::MAX, 1)
+        );
+    }
+
+    #[test]
+    fn macro_ranges() {
+        let ranges = crate::s![.., 1..3, 2..=4; 2, -1];
+        assert_eq!(
+            ranges,
+            [
+                SliceRange::from(..),
+                SliceRange::from(1..3),
+                SliceRange::from(2..=4).step(2),
+                SliceRange::from(-1),
+            ]
+        );
+        assert_eq!(crate::s![0..4;2,].len(), 1);
+    }
+}
@@ -1,13 +1,19 @@
-use crate::{engine::Engine, error::Error};
+use crate::{engine::Engine, error::Error, slice::SliceRange, QuantizationType};
 use deepviewrt_sys as ffi;
 use std::{
     cell::Cell,
-    ffi::c_void,
+    ffi::{c_int, c_void, CString},
     io,
-    ops::{Deref, DerefMut},
+    marker::PhantomData,
+    ops::{BitOr, BitOrAssign, Deref, DerefMut},
+    path::Path,
 };
 
-#[derive(Debug)]
+/// The maximum rank of a tensor supported by DeepViewRT.
+pub const MAX_DIMS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TensorType {
     RAW = 0,
     STR = 1,
@@ -47,11 +53,154 @@ impl TryFrom<u32> for TensorType {
     }
 }
 
+impl TensorType {
+    pub(crate) fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            TensorType::I8
+                | TensorType::U8
+                | TensorType::I16
+                | TensorType::U16
+                | TensorType::I32
+                | TensorType::U32
+                | TensorType::I64
+                | TensorType::U64
+        )
+    }
+
+    /// Size in bytes of a single element, `None` for string tensors.
+    pub(crate) fn element_size(&self) -> Option<usize> {
+        match self {
+            TensorType::RAW | TensorType::I8 | TensorType::U8 => Some(1),
+            TensorType::I16 | TensorType::U16 | TensorType::F16 => Some(2),
+            TensorType::I32 | TensorType::U32 | TensorType::F32 => Some(4),
+            TensorType::I64 | TensorType::U64 | TensorType::F64 => Some(8),
+            TensorType::STR => None,
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Rust types which may be stored as tensor elements.  The trait is sealed as
+/// the tensor type must exactly describe the element's memory layout.
+pub trait TensorElement: private::Sealed + Copy + 'static {
+    const TYPE: TensorType;
+}
+
+macro_rules! tensor_element {
+    ($t:ty, $ttype:ident) => {
+        impl private::Sealed for $t {}
+        impl TensorElement for $t {
+            const TYPE: TensorType = TensorType::$ttype;
+        }
+    };
+}
+
+tensor_element!(i8, I8);
+tensor_element!(u8, U8);
+tensor_element!(i16, I16);
+tensor_element!(u16, U16);
+tensor_element!(i32, I32);
+tensor_element!(u32, U32);
+tensor_element!(i64, I64);
+tensor_element!(u64, U64);
+tensor_element!(f32, F32);
+tensor_element!(f64, F64);
+#[cfg(feature = "half")]
+tensor_element!(half::f16, F16);
+
+/// Processing applied when decoding images with [`Tensor::load_image`].  The
+/// flags may be combined, for example `ImageProc::SIGNED_NORM |
+/// ImageProc::MIRROR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ImageProc(u32);
+
+impl ImageProc {
+    /// The image is flipped vertically.
+    pub const FLIP: ImageProc = ImageProc(0x2000);
+    /// Pixels are normalized using the ImageNet mean and standard deviation.
+    pub const IMAGENET: ImageProc = ImageProc(0x0008);
+    /// The image is mirrored horizontally.
+    pub const MIRROR: ImageProc = ImageProc(0x1000);
+    /// Pixels are cast to the tensor type without normalization.
+    pub const NONE: ImageProc = ImageProc(0);
+    /// Pixels are normalized to the range [-1, 1].
+    pub const SIGNED_NORM: ImageProc = ImageProc(0x0004);
+    /// Pixels are normalized to the range [0, 1].
+    pub const UNSIGNED_NORM: ImageProc = ImageProc(0x0001);
+    /// Per-image standardization, as tf.image.per_image_standardization.
+    pub const WHITENING: ImageProc = ImageProc(0x0002);
+
+    /// The raw `NN_IMAGE_PROC` flags passed to the library.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Whether all of the flags in `other` are set.
+    pub const fn contains(self, other: ImageProc) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ImageProc {
+    type Output = ImageProc;
+
+    fn bitor(self, rhs: ImageProc) -> ImageProc {
+        ImageProc(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ImageProc {
+    fn bitor_assign(&mut self, rhs: ImageProc) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Padding strategy used by [`Tensor::compute_padding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// Pads the input so the output keeps the input's shape.
+    Same,
+    /// No padding, the output shrinks according to the window.
+    Valid,
+}
+
+/// Paddings computed by [`Tensor::compute_padding`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paddings {
+    /// The output shape after applying the window.
+    pub shape: Vec<i32>,
+    /// Lead-in padding for each dimension.
+    pub head: Vec<i32>,
+    /// Lead-out padding for each dimension.
+    pub tail: Vec<i32>,
+}
+
+/// Quantization parameters of a tensor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuantParams {
+    None,
+    PerTensor {
+        scale: f32,
+        zero: i32,
+    },
+    /// One scale and zero-point per channel along `axis`.
+    PerChannel {
+        axis: i32,
+        scales: Vec<f32>,
+        zeros: Vec<i32>,
+    },
+    /// Dynamic fixed point, which DeepViewRT does not currently support.
+    Dfp,
+}
+
 pub struct Tensor {
     owned: bool,
     ptr: *mut ffi::NNTensor,
     engine: Cell<Option<Engine>>,
-    scales: Option<Vec<f32>>,
 }
 
 pub struct TensorData<'a, T> {
@@ -59,7 +208,7 @@ pub struct TensorData<'a, T> {
     data: &'a [T],
 }
 
-impl<'a, T> Deref for TensorData<'_, T> {
+impl<T> Deref for TensorData<'_, T> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
@@ -72,7 +221,7 @@ pub struct TensorDataMut<'a, T> {
     data: &'a mut [T],
 }
 
-impl<'a, T> Deref for TensorDataMut<'_, T> {
+impl<T> Deref for TensorDataMut<'_, T> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
@@ -80,7 +229,7 @@ impl<'a, T> Deref for TensorDataMut<'_, T> {
     }
 }
 
-impl<'a, T> DerefMut for TensorDataMut<'_, T> {
+impl<T> DerefMut for TensorDataMut<'_, T> {
     fn deref_mut(&mut self) -> &mut [T] {
         self.data
     }
@@ -99,12 +248,26 @@ impl Deref for Tensor {
 
 impl Tensor {
     pub fn new() -> Result<Self, Error> {
-        let ptr = unsafe {
-            ffi::nn_tensor_init(
-                std::ptr::null::<c_void>() as *mut c_void,
-                std::ptr::null::<ffi::nn_engine>() as *mut ffi::nn_engine,
-            )
-        };
+        Self::init(std::ptr::null_mut())
+    }
+
+    /// Creates a tensor managed by the engine, see [`EngineTensor::new`] for a
+    /// safe alternative.
+    ///
+    /// # Safety
+    /// The engine must outlive the returned tensor, as releasing the tensor
+    /// calls back into the engine.
+    pub unsafe fn with_engine(engine: &Engine) -> Result<Self, Error> {
+        Self::init(engine.to_ptr_mut())
+    }
+
+    /// Creates a builder which allocates a tensor of a given type and shape.
+    pub fn builder<'e>() -> TensorBuilder<'e> {
+        TensorBuilder::default()
+    }
+
+    fn init(engine: *mut ffi::NNEngine) -> Result<Self, Error> {
+        let ptr = unsafe { ffi::nn_tensor_init(std::ptr::null::<c_void>() as *mut c_void, engine) };
         if ptr.is_null() {
             let err_kind = io::Error::last_os_error().kind();
             return Err(Error::IoError(err_kind));
@@ -114,13 +277,17 @@ impl Tensor {
             owned: true,
             engine: Cell::new(None),
             ptr,
-            scales: None,
         })
     }
 
-    pub fn alloc(&self, ttype: TensorType, n_dims: i32, shape: &[i32; 3]) -> Result<(), Error> {
+    /// Allocates the tensor's memory for the given type and shape, which must
+    /// have between 1 and [`MAX_DIMS`] dimensions.
+    pub fn alloc(&mut self, ttype: TensorType, shape: &[i32]) -> Result<(), Error> {
+        check_shape(shape)?;
         let ttype_c_uint = (ttype as u32) as std::os::raw::c_uint;
-        let ret = unsafe { ffi::nn_tensor_alloc(self.ptr, ttype_c_uint, n_dims, shape.as_ptr()) };
+        let ret = unsafe {
+            ffi::nn_tensor_alloc(self.ptr, ttype_c_uint, shape.len() as i32, shape.as_ptr())
+        };
         if ret != ffi::NNError_NN_SUCCESS {
             return Err(Error::from(ret));
         }
@@ -172,7 +339,8 @@ impl Tensor {
         Ok(())
     }
 
-    pub fn quantize(&self, dest: &mut Self, axis: i32) -> Result<(), Error> {
+    pub fn quantize<'d>(&self, dest: impl Into<TensorMut<'d>>, axis: i32) -> Result<(), Error> {
+        let dest = dest.into();
         let ret = unsafe { ffi::nn_tensor_quantize(dest.to_mut_ptr(), self.ptr, axis) };
         if ret != ffi::NNError_NN_SUCCESS {
             return Err(Error::from(ret));
@@ -181,7 +349,7 @@ impl Tensor {
         Ok(())
     }
 
-    pub fn quantize_buffer(&self, src: &[f32], axis: i32) -> Result<(), Error> {
+    pub fn quantize_buffer(&mut self, src: &[f32], axis: i32) -> Result<(), Error> {
         let ret = unsafe {
             ffi::nn_tensor_quantize_buffer(self.to_mut_ptr(), src.len(), src.as_ptr(), axis)
         };
@@ -192,7 +360,8 @@ impl Tensor {
         Ok(())
     }
 
-    pub fn dequantize(&self, dest: &mut Self) -> Result<(), Error> {
+    pub fn dequantize<'d>(&self, dest: impl Into<TensorMut<'d>>) -> Result<(), Error> {
+        let dest = dest.into();
         let ret = unsafe { ffi::nn_tensor_dequantize(dest.to_mut_ptr(), self.ptr) };
         if ret != ffi::NNError_NN_SUCCESS {
             return Err(Error::from(ret));
@@ -202,6 +371,14 @@ impl Tensor {
     }
 
     pub fn dequantize_buffer(&self, dest: &mut [f32]) -> Result<(), Error> {
+        let volume = self.volume() as usize;
+        if dest.len() < volume {
+            return Err(Error::ShapeMismatch(format!(
+                "buffer of {} elements cannot hold tensor volume {}",
+                dest.len(),
+                volume
+            )));
+        }
         let ret =
             unsafe { ffi::nn_tensor_dequantize_buffer(self.ptr, dest.len(), dest.as_mut_ptr()) };
         if ret != ffi::NNError_NN_SUCCESS {
@@ -211,7 +388,141 @@ impl Tensor {
         Ok(())
     }
 
-    pub fn set_tensor_type(&self, tensor_type: TensorType) -> Result<(), Error> {
+    /// Requantizes this tensor into `dest` using the destination's
+    /// quantization parameters.  Both tensors must be per-tensor quantized
+    /// I8 or U8.
+    pub fn requantize<'d>(&self, dest: impl Into<TensorMut<'d>>) -> Result<(), Error> {
+        let dest = dest.into();
+        for ttype in [self.tensor_type(), dest.tensor_type()] {
+            if ttype != TensorType::I8 && ttype != TensorType::U8 {
+                return Err(Error::WrapperError(format!(
+                    "requantize requires I8 or U8 tensors, found {:?}",
+                    ttype
+                )));
+            }
+        }
+        for found in [self.quantization_type(), dest.quantization_type()] {
+            if found != QuantizationType::TypeAffinePerTensor {
+                return Err(Error::QuantizationMismatch {
+                    expected: QuantizationType::TypeAffinePerTensor,
+                    found,
+                });
+            }
+        }
+        let ret = unsafe { ffi::nn_tensor_requantize(dest.to_mut_ptr(), self.ptr) };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the tensor as floats.  Integer tensors are dequantized according
+    /// to the tensor's own quantization parameters while float tensors are
+    /// converted, F16 requires the `half` feature.
+    pub fn to_f32_vec(&self) -> Result<Vec<f32>, Error> {
+        match self.tensor_type() {
+            TensorType::F32 => Ok(self.mapro::<f32>()?.to_vec()),
+            TensorType::F64 => Ok(self.mapro::<f64>()?.iter().map(|v| *v as f32).collect()),
+            #[cfg(feature = "half")]
+            TensorType::F16 => Ok(self
+                .mapro::<half::f16>()?
+                .iter()
+                .map(|v| v.to_f32())
+                .collect()),
+            ttype if ttype.is_integer() => {
+                let mut dest = vec![0.0; self.volume() as usize];
+                self.dequantize_buffer(&mut dest)?;
+                Ok(dest)
+            }
+            found => Err(Error::TypeMismatch {
+                expected: TensorType::F32,
+                found,
+            }),
+        }
+    }
+
+    /// Writes floats into the tensor.  Integer tensors are quantized according
+    /// to the tensor's own quantization parameters, those without parameters
+    /// have per-tensor parameters computed from `src`.  Float tensors are
+    /// converted, F16 requires the `half` feature.
+    pub fn from_f32_slice(&mut self, src: &[f32]) -> Result<(), Error> {
+        let volume = self.volume() as usize;
+        if src.len() != volume {
+            return Err(Error::ShapeMismatch(format!(
+                "buffer of {} elements does not match tensor volume {}",
+                src.len(),
+                volume
+            )));
+        }
+        match self.tensor_type() {
+            TensorType::F32 => self.mapwo::<f32>()?.copy_from_slice(src),
+            TensorType::F64 => {
+                let mut dest = self.mapwo::<f64>()?;
+                for (dst, src) in dest.iter_mut().zip(src) {
+                    *dst = *src as f64;
+                }
+            }
+            #[cfg(feature = "half")]
+            TensorType::F16 => {
+                use half::slice::HalfFloatSliceExt;
+                self.mapwo::<half::f16>()?.convert_from_f32_slice(src);
+            }
+            ttype if ttype.is_integer() => self.quantize_buffer(src, -1)?,
+            found => {
+                return Err(Error::TypeMismatch {
+                    expected: TensorType::F32,
+                    found,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the JPEG or PNG image in `image` into the tensor, converting to
+    /// the tensor's type and applying the `proc` processing flags.
+    pub fn load_image(&mut self, image: &[u8], proc: ImageProc) -> Result<(), Error> {
+        let ret = unsafe {
+            ffi::nn_tensor_load_image_ex(
+                self.ptr,
+                image.as_ptr() as *const c_void,
+                image.len(),
+                proc.bits(),
+            )
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+
+        Ok(())
+    }
+
+    /// Decodes the image file at `path` into the tensor, see
+    /// [`Tensor::load_image`].
+    pub fn load_image_file<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        proc: ImageProc,
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        let filename = match path.to_str().map(CString::new) {
+            Some(Ok(filename)) => filename,
+            _ => {
+                return Err(Error::WrapperError(format!(
+                    "invalid image path {}",
+                    path.display()
+                )))
+            }
+        };
+        let ret = unsafe { ffi::nn_tensor_load_file_ex(self.ptr, filename.as_ptr(), proc.bits()) };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+
+        Ok(())
+    }
+
+    pub fn set_tensor_type(&mut self, tensor_type: TensorType) -> Result<(), Error> {
         let tensor_type_ = TensorType::try_from(tensor_type as u32).unwrap();
         let ret = unsafe { ffi::nn_tensor_set_type(self.ptr, tensor_type_ as ffi::NNTensorType) };
         if ret != ffi::NNError_NN_SUCCESS {
@@ -253,6 +564,17 @@ impl Tensor {
         unsafe { ffi::nn_tensor_size(self.ptr) }
     }
 
+    /// Nanoseconds taken by the last operation which wrote into this tensor,
+    /// excluding map/unmap time.
+    pub fn time(&self) -> i64 {
+        unsafe { ffi::nn_tensor_time(self.ptr) }
+    }
+
+    /// Nanoseconds spent in the last map/unmap pair of this tensor.
+    pub fn io_time(&self) -> i64 {
+        unsafe { ffi::nn_tensor_io_time(self.ptr) }
+    }
+
     pub fn axis(&self) -> i16 {
         unsafe { ffi::nn_tensor_axis(self.ptr) as i16 }
     }
@@ -275,17 +597,126 @@ impl Tensor {
         unsafe { Ok(std::slice::from_raw_parts(ret, zeros)) }
     }
 
+    /// Checks a quantization parameter count is either per-tensor or matches
+    /// the tensor's channel dimension along `axis`.
+    fn check_channels(&self, axis: i32, len: usize, what: &str) -> Result<(), Error> {
+        if len <= 1 {
+            return Ok(());
+        }
+        let channels = match self.shape().get(axis as usize) {
+            Some(channels) if axis >= 0 => *channels as usize,
+            _ => {
+                return Err(Error::InvalidShape(format!(
+                    "{} {} per-channel values require a channel axis",
+                    len, what
+                )))
+            }
+        };
+        if len != channels {
+            return Err(Error::ShapeMismatch(format!(
+                "{} {} do not match {} channels on axis {}",
+                len, what, channels, axis
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sets the quantization scales, either a single per-tensor scale or one
+    /// per channel along [`Tensor::axis`].
     pub fn set_scales(&mut self, scales: &[f32]) -> Result<(), Error> {
-        self.scales = Some(scales.to_vec());
-        if scales.len() < (self.axis() as usize) || scales.len() != 1 {
-            return Err(Error::WrapperError(String::from(
-                "scales should either have length of 1 or equal to channel_dimension (axis)",
+        self.check_channels(self.axis().into(), scales.len(), "scales")?;
+        let (ptr, own) = c_copy(scales)?;
+        unsafe { ffi::nn_tensor_set_scales(self.ptr, scales.len(), ptr, own) };
+        Ok(())
+    }
+
+    /// Sets the quantization zero-points, either a single per-tensor value or
+    /// one per channel along [`Tensor::axis`].
+    pub fn set_zeros(&mut self, zeros: &[i32]) -> Result<(), Error> {
+        self.check_channels(self.axis().into(), zeros.len(), "zeros")?;
+        let (ptr, own) = c_copy(zeros)?;
+        unsafe { ffi::nn_tensor_set_zeros(self.ptr, zeros.len(), ptr, own) };
+        Ok(())
+    }
+
+    /// Sets the channel axis used for per-channel quantization.
+    fn check_axis(&self, axis: i32) -> Result<(), Error> {
+        if axis < 0 || axis >= self.dims() {
+            return Err(Error::InvalidShape(format!(
+                "axis {} out of range for rank {}",
+                axis,
+                self.dims()
             )));
         }
-        unsafe { ffi::nn_tensor_set_scales(self.ptr, scales.len(), scales.as_ptr(), 0) };
         Ok(())
     }
 
+    pub fn set_axis(&mut self, axis: i32) -> Result<(), Error> {
+        self.check_axis(axis)?;
+        unsafe { ffi::nn_tensor_set_axis(self.ptr, axis) };
+        Ok(())
+    }
+
+    pub fn quantization_type(&self) -> QuantizationType {
+        let ret = unsafe { ffi::nn_tensor_quantization_type(self.ptr) };
+        QuantizationType::try_from(ret).unwrap_or(QuantizationType::TypeNone)
+    }
+
+    pub fn quant_params(&self) -> QuantParams {
+        let scales = self.scales().unwrap_or(&[]);
+        let zeros = self.zeros().unwrap_or(&[]);
+        match self.quantization_type() {
+            QuantizationType::TypeNone => QuantParams::None,
+            QuantizationType::TypeAffinePerTensor => QuantParams::PerTensor {
+                scale: scales.first().copied().unwrap_or(1.0),
+                zero: zeros.first().copied().unwrap_or(0),
+            },
+            QuantizationType::TypeAffinePerChannel => QuantParams::PerChannel {
+                axis: self.axis() as i32,
+                scales: scales.to_vec(),
+                zeros: zeros.to_vec(),
+            },
+            QuantizationType::TypeDFP => QuantParams::Dfp,
+        }
+    }
+
+    pub fn set_quant_params(&mut self, params: &QuantParams) -> Result<(), Error> {
+        match params {
+            QuantParams::None => {
+                self.set_scales(&[])?;
+                self.set_zeros(&[])
+            }
+            QuantParams::PerTensor { scale, zero } => {
+                self.set_scales(&[*scale])?;
+                self.set_zeros(&[*zero])
+            }
+            QuantParams::PerChannel {
+                axis,
+                scales,
+                zeros,
+            } => {
+                if zeros.len() > 1 && zeros.len() != scales.len() {
+                    return Err(Error::ShapeMismatch(format!(
+                        "{} zeros do not match {} scales",
+                        zeros.len(),
+                        scales.len()
+                    )));
+                }
+                // Validate everything against the new axis before changing
+                // any of the tensor's parameters.
+                self.check_axis(*axis)?;
+                self.check_channels(*axis, scales.len(), "scales")?;
+                self.check_channels(*axis, zeros.len(), "zeros")?;
+                self.set_axis(*axis)?;
+                self.set_scales(scales)?;
+                self.set_zeros(zeros)
+            }
+            QuantParams::Dfp => Err(Error::WrapperError(String::from(
+                "DFP quantization is not supported",
+            ))),
+        }
+    }
+
     pub fn randomize(&mut self) -> Result<(), Error> {
         let err = unsafe { ffi::nn_tensor_randomize(self.ptr) };
         if err != ffi::NNError_NN_SUCCESS {
@@ -310,107 +741,74 @@ impl Tensor {
         Ok(ret)
     }
 
+    fn mapwo_raw(&self) -> Result<*mut ::std::os::raw::c_void, Error> {
+        let ret = unsafe { ffi::nn_tensor_mapwo(self.ptr) };
+        if ret.is_null() {
+            return Err(Error::WrapperError("nn_tensor_mapwo failed".to_string()));
+        }
+        Ok(ret)
+    }
+
     pub fn mapro_u8(&self) -> Result<TensorData<'_, u8>, Error> {
-        let ptr = self.mapro_raw()? as *const u8;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<u8>()
     }
 
     pub fn mapro_u16(&self) -> Result<TensorData<'_, u16>, Error> {
-        let ptr = self.mapro_raw()? as *const u16;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<u16>()
     }
 
     pub fn mapro_u32(&self) -> Result<TensorData<'_, u32>, Error> {
-        let ptr = self.mapro_raw()? as *const u32;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<u32>()
     }
 
     pub fn mapro_u64(&self) -> Result<TensorData<'_, u64>, Error> {
-        let ptr = self.mapro_raw()? as *const u64;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<u64>()
     }
 
     pub fn mapro_i8(&self) -> Result<TensorData<'_, i8>, Error> {
-        let ptr = self.mapro_raw()? as *const i8;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<i8>()
     }
 
     pub fn mapro_i16(&self) -> Result<TensorData<'_, i16>, Error> {
-        let ptr = self.mapro_raw()? as *const i16;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<i16>()
     }
 
     pub fn mapro_i32(&self) -> Result<TensorData<'_, i32>, Error> {
-        let ptr = self.mapro_raw()? as *const i32;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<i32>()
     }
 
     pub fn mapro_i64(&self) -> Result<TensorData<'_, i64>, Error> {
-        let ptr = self.mapro_raw()? as *const i64;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<i64>()
     }
 
     pub fn mapro_f32(&self) -> Result<TensorData<'_, f32>, Error> {
-        let ptr = self.mapro_raw()? as *const f32;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<f32>()
     }
 
     pub fn mapro_f64(&self) -> Result<TensorData<'_, f64>, Error> {
-        let ptr = self.mapro_raw()? as *const f64;
-        let volume = self.volume();
-        let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
-        Ok(TensorData {
-            tensor: self,
-            data: sret,
-        })
+        self.mapro::<f64>()
     }
 
-    pub fn mapro<T>(&self) -> Result<TensorData<'_, T>, Error> {
+    #[cfg(feature = "half")]
+    pub fn mapro_f16(&self) -> Result<TensorData<'_, half::f16>, Error> {
+        self.mapro::<half::f16>()
+    }
+
+    fn check_type<T: TensorElement>(&self) -> Result<(), Error> {
+        let found = self.tensor_type();
+        if found != T::TYPE {
+            return Err(Error::TypeMismatch {
+                expected: T::TYPE,
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Maps the tensor for reading as elements of `T`, which must match the
+    /// tensor's type.
+    pub fn mapro<T: TensorElement>(&self) -> Result<TensorData<'_, T>, Error> {
+        self.check_type::<T>()?;
         let ptr = self.mapro_raw()? as *const T;
         let volume = self.volume();
         let sret = unsafe { std::slice::from_raw_parts(ptr, volume as usize) };
@@ -420,8 +818,101 @@ impl Tensor {
         })
     }
 
+    pub fn maprw_u8(&mut self) -> Result<TensorDataMut<'_, u8>, Error> {
+        self.maprw::<u8>()
+    }
+
+    pub fn maprw_u16(&mut self) -> Result<TensorDataMut<'_, u16>, Error> {
+        self.maprw::<u16>()
+    }
+
+    pub fn maprw_u32(&mut self) -> Result<TensorDataMut<'_, u32>, Error> {
+        self.maprw::<u32>()
+    }
+
+    pub fn maprw_u64(&mut self) -> Result<TensorDataMut<'_, u64>, Error> {
+        self.maprw::<u64>()
+    }
+
+    pub fn maprw_i8(&mut self) -> Result<TensorDataMut<'_, i8>, Error> {
+        self.maprw::<i8>()
+    }
+
+    pub fn maprw_i16(&mut self) -> Result<TensorDataMut<'_, i16>, Error> {
+        self.maprw::<i16>()
+    }
+
+    pub fn maprw_i32(&mut self) -> Result<TensorDataMut<'_, i32>, Error> {
+        self.maprw::<i32>()
+    }
+
+    pub fn maprw_i64(&mut self) -> Result<TensorDataMut<'_, i64>, Error> {
+        self.maprw::<i64>()
+    }
+
     pub fn maprw_f32(&mut self) -> Result<TensorDataMut<'_, f32>, Error> {
-        let ptr = self.maprw_raw()? as *mut f32;
+        self.maprw::<f32>()
+    }
+
+    pub fn maprw_f64(&mut self) -> Result<TensorDataMut<'_, f64>, Error> {
+        self.maprw::<f64>()
+    }
+
+    #[cfg(feature = "half")]
+    pub fn maprw_f16(&mut self) -> Result<TensorDataMut<'_, half::f16>, Error> {
+        self.maprw::<half::f16>()
+    }
+
+    pub fn mapwo_u8(&mut self) -> Result<TensorDataMut<'_, u8>, Error> {
+        self.mapwo::<u8>()
+    }
+
+    pub fn mapwo_u16(&mut self) -> Result<TensorDataMut<'_, u16>, Error> {
+        self.mapwo::<u16>()
+    }
+
+    pub fn mapwo_u32(&mut self) -> Result<TensorDataMut<'_, u32>, Error> {
+        self.mapwo::<u32>()
+    }
+
+    pub fn mapwo_u64(&mut self) -> Result<TensorDataMut<'_, u64>, Error> {
+        self.mapwo::<u64>()
+    }
+
+    pub fn mapwo_i8(&mut self) -> Result<TensorDataMut<'_, i8>, Error> {
+        self.mapwo::<i8>()
+    }
+
+    pub fn mapwo_i16(&mut self) -> Result<TensorDataMut<'_, i16>, Error> {
+        self.mapwo::<i16>()
+    }
+
+    pub fn mapwo_i32(&mut self) -> Result<TensorDataMut<'_, i32>, Error> {
+        self.mapwo::<i32>()
+    }
+
+    pub fn mapwo_i64(&mut self) -> Result<TensorDataMut<'_, i64>, Error> {
+        self.mapwo::<i64>()
+    }
+
+    pub fn mapwo_f32(&mut self) -> Result<TensorDataMut<'_, f32>, Error> {
+        self.mapwo::<f32>()
+    }
+
+    pub fn mapwo_f64(&mut self) -> Result<TensorDataMut<'_, f64>, Error> {
+        self.mapwo::<f64>()
+    }
+
+    #[cfg(feature = "half")]
+    pub fn mapwo_f16(&mut self) -> Result<TensorDataMut<'_, half::f16>, Error> {
+        self.mapwo::<half::f16>()
+    }
+
+    /// Maps the tensor for reading and writing as elements of `T`, which must
+    /// match the tensor's type.
+    pub fn maprw<T: TensorElement>(&mut self) -> Result<TensorDataMut<'_, T>, Error> {
+        self.check_type::<T>()?;
+        let ptr = self.maprw_raw()? as *mut T;
         let volume = self.volume();
         let sret = unsafe { std::slice::from_raw_parts_mut(ptr, volume as usize) };
         Ok(TensorDataMut {
@@ -430,8 +921,13 @@ impl Tensor {
         })
     }
 
-    pub fn maprw<T>(&mut self) -> Result<TensorDataMut<'_, T>, Error> {
-        let ptr = self.maprw_raw()? as *mut T;
+    /// Maps the tensor for writing as elements of `T`, which must match the
+    /// tensor's type.  The contents of the mapping are undefined until written
+    /// which lets accelerator engines skip copying the tensor to the host, so
+    /// the whole tensor should be overwritten.
+    pub fn mapwo<T: TensorElement>(&mut self) -> Result<TensorDataMut<'_, T>, Error> {
+        self.check_type::<T>()?;
+        let ptr = self.mapwo_raw()? as *mut T;
         let volume = self.volume();
         let sret = unsafe { std::slice::from_raw_parts_mut(ptr, volume as usize) };
         Ok(TensorDataMut {
@@ -440,10 +936,38 @@ impl Tensor {
         })
     }
 
+    /// Maps the tensor's underlying bytes for reading regardless of its type.
+    pub fn map_bytes(&self) -> Result<TensorData<'_, u8>, Error> {
+        let ptr = self.mapro_raw()? as *const u8;
+        let size = self.size();
+        let sret = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+        Ok(TensorData {
+            tensor: self,
+            data: sret,
+        })
+    }
+
+    /// Maps the tensor's underlying bytes for reading and writing regardless of
+    /// its type.
+    pub fn map_bytes_mut(&mut self) -> Result<TensorDataMut<'_, u8>, Error> {
+        let ptr = self.maprw_raw()? as *mut u8;
+        let size = self.size();
+        let sret = unsafe { std::slice::from_raw_parts_mut(ptr, size as usize) };
+        Ok(TensorDataMut {
+            tensor: self,
+            data: sret,
+        })
+    }
+
     unsafe fn unmap(&self) {
         unsafe { ffi::nn_tensor_unmap(self.ptr) };
     }
 
+    /// Wraps a raw tensor pointer.
+    ///
+    /// # Safety
+    /// The pointer must reference a valid tensor which outlives the returned
+    /// object.  When `owned` is true the tensor will be released on drop.
     pub unsafe fn from_ptr(ptr: *mut ffi::NNTensor, owned: bool) -> Result<Self, Error> {
         if ptr.is_null() {
             return Err(Error::WrapperError(String::from("ptr is null")));
@@ -453,7 +977,348 @@ impl Tensor {
             owned,
             engine: Cell::new(None),
             ptr,
-            scales: None,
+        })
+    }
+
+    /// Creates a tensor of `dtype` and `shape` which maps the parent's memory
+    /// starting at `offset`, counted in elements of `dtype`.  The view
+    /// borrows this tensor mutably so the two cannot be mapped at once, and
+    /// must fit within this tensor's memory.
+    pub fn view(
+        &mut self,
+        dtype: TensorType,
+        shape: &[i32],
+        offset: i32,
+    ) -> Result<TensorView<'_>, Error> {
+        let volume = check_shape(shape)?;
+        if offset < 0 {
+            return Err(Error::WrapperError(format!(
+                "view offset {} must not be negative",
+                offset
+            )));
+        }
+        let element_size = match dtype.element_size() {
+            Some(size) => size,
+            None => {
+                return Err(Error::WrapperError(String::from(
+                    "string tensors cannot be viewed",
+                )))
+            }
+        };
+        // Bounding the end in elements also bounds it should the library count
+        // the offset in bytes.
+        let end = (offset as usize + volume as usize).checked_mul(element_size);
+        let size = self.size().max(0) as usize;
+        match end {
+            Some(end) if end <= size => {}
+            _ => {
+                return Err(Error::ShapeMismatch(format!(
+                    "view of {:?} at offset {} exceeds the parent's {} bytes",
+                    shape, offset, size
+                )))
+            }
+        }
+        // The view borrows the parent which keeps the parent's engine alive.
+        let tensor = match self.engine() {
+            Some(engine) => unsafe { Tensor::with_engine(engine)? },
+            None => Tensor::new()?,
+        };
+        let ret = unsafe {
+            ffi::nn_tensor_view(
+                tensor.ptr,
+                dtype as ffi::NNTensorType,
+                shape.len() as i32,
+                shape.as_ptr(),
+                self.ptr,
+                offset,
+            )
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(TensorView {
+            tensor,
+            _parent: PhantomData,
+        })
+    }
+
+    /// Copies the region `head..tail` along each of `axes` into a new tensor.
+    pub fn slice(&self, axes: &[i32], head: &[i32], tail: &[i32]) -> Result<Tensor, Error> {
+        let shape = slice_shape(self.shape(), axes, head, tail, &vec![1; axes.len()])?;
+        let mut output = Tensor::new()?;
+        output.alloc(self.tensor_type(), &shape)?;
+        let ret = unsafe {
+            ffi::nn_tensor_slice(
+                output.ptr,
+                self.ptr,
+                axes.len() as i32,
+                axes.as_ptr(),
+                head.as_ptr(),
+                tail.as_ptr(),
+            )
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(output)
+    }
+
+    /// Copies every `strides` element of the region `head..tail` along each of
+    /// `axes` into a new tensor.
+    pub fn strided_slice(
+        &self,
+        axes: &[i32],
+        head: &[i32],
+        tail: &[i32],
+        strides: &[i32],
+    ) -> Result<Tensor, Error> {
+        let shape = slice_shape(self.shape(), axes, head, tail, strides)?;
+        let mut output = Tensor::new()?;
+        output.alloc(self.tensor_type(), &shape)?;
+        let ret = unsafe {
+            ffi::nn_tensor_strided_slice(
+                output.ptr,
+                self.ptr,
+                axes.len() as i32,
+                axes.as_ptr(),
+                head.as_ptr(),
+                tail.as_ptr(),
+                strides.as_ptr(),
+            )
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(output)
+    }
+
+    /// Copies a slice described by one range per leading dimension into a new
+    /// tensor, for example `tensor.slice_ranges(&s![.., 10..20, ..])`.
+    pub fn slice_ranges(&self, ranges: &[SliceRange]) -> Result<Tensor, Error> {
+        let shape = self.shape();
+        if ranges.len() > shape.len() {
+            return Err(Error::InvalidShape(format!(
+                "{} ranges given for rank {}",
+                ranges.len(),
+                shape.len()
+            )));
+        }
+        let mut axes = Vec::with_capacity(ranges.len());
+        let mut head = Vec::with_capacity(ranges.len());
+        let mut tail = Vec::with_capacity(ranges.len());
+        let mut strides = Vec::with_capacity(ranges.len());
+        for (axis, range) in ranges.iter().enumerate() {
+            let (h, t, s) = range.resolve(shape[axis])?;
+            axes.push(axis as i32);
+            head.push(h);
+            tail.push(t);
+            strides.push(s);
+        }
+        if strides.iter().all(|s| *s == 1) {
+            self.slice(&axes, &head, &tail)
+        } else {
+            self.strided_slice(&axes, &head, &tail, &strides)
+        }
+    }
+
+    /// Reshapes the tensor in place, the new shape must hold the same number of
+    /// elements.
+    pub fn reshape(&mut self, shape: &[i32]) -> Result<(), Error> {
+        let volume = check_shape(shape)?;
+        if volume != self.volume() {
+            return Err(Error::ShapeMismatch(format!(
+                "cannot reshape {:?} into {:?}",
+                self.shape(),
+                shape
+            )));
+        }
+        let ret = unsafe { ffi::nn_tensor_reshape(self.ptr, shape.len() as i32, shape.as_ptr()) };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(())
+    }
+
+    /// Copies the tensor into a new tensor with its dimensions reordered, for
+    /// example `permute(&[0, 2, 3, 1])` converts NCHW to NHWC.
+    pub fn permute(&self, order: &[i32]) -> Result<Tensor, Error> {
+        let shape = self.shape();
+        let mut seen = [false; MAX_DIMS];
+        if order.len() != shape.len() {
+            return Err(Error::InvalidShape(format!(
+                "order {:?} does not match rank {}",
+                order,
+                shape.len()
+            )));
+        }
+        for axis in order {
+            if *axis < 0 || *axis as usize >= shape.len() || seen[*axis as usize] {
+                return Err(Error::InvalidShape(format!(
+                    "order {:?} is not a permutation of the tensor's axes",
+                    order
+                )));
+            }
+            seen[*axis as usize] = true;
+        }
+        let permuted: Vec<i32> = order.iter().map(|axis| shape[*axis as usize]).collect();
+
+        let mut output = Tensor::new()?;
+        output.alloc(self.tensor_type(), &permuted)?;
+        let ret = unsafe {
+            ffi::nn_tensor_shuffle(output.ptr, self.ptr, order.len() as i32, order.as_ptr())
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(output)
+    }
+
+    /// Concatenates the tensors along `axis` into a new tensor.  All inputs
+    /// must share a type and match in every dimension except `axis`.
+    pub fn concat(inputs: &[&Tensor], axis: i32) -> Result<Tensor, Error> {
+        let first = match inputs.first() {
+            Some(first) => first,
+            None => {
+                return Err(Error::InvalidShape(String::from(
+                    "concat requires at least one input",
+                )))
+            }
+        };
+        let mut shape = first.shape().to_vec();
+        if axis < 0 || axis as usize >= shape.len() {
+            return Err(Error::InvalidShape(format!(
+                "axis {} out of range for rank {}",
+                axis,
+                shape.len()
+            )));
+        }
+        for input in &inputs[1..] {
+            if input.tensor_type() != first.tensor_type() {
+                return Err(Error::TypeMismatch {
+                    expected: first.tensor_type(),
+                    found: input.tensor_type(),
+                });
+            }
+            let other = input.shape();
+            let compatible = other.len() == shape.len()
+                && (0..shape.len()).all(|i| i == axis as usize || other[i] == shape[i]);
+            if !compatible {
+                return Err(Error::ShapeMismatch(format!(
+                    "cannot concat {:?} with {:?} along axis {}",
+                    first.shape(),
+                    other,
+                    axis
+                )));
+            }
+            shape[axis as usize] += other[axis as usize];
+        }
+
+        let mut output = Tensor::new()?;
+        output.alloc(first.tensor_type(), &shape)?;
+        let mut ptrs: Vec<*mut ffi::NNTensor> = inputs.iter().map(|input| input.ptr).collect();
+        let ret = unsafe {
+            ffi::nn_tensor_concat(output.ptr, ptrs.len() as i32, ptrs.as_mut_ptr(), axis)
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(output)
+    }
+
+    /// Copies the tensor into a new tensor padded by `head` and `tail`
+    /// elements on each dimension, filling the padding with `constant`.
+    pub fn pad(&self, head: &[i32], tail: &[i32], constant: f64) -> Result<Tensor, Error> {
+        let shape = self.shape();
+        if head.len() != shape.len() || tail.len() != shape.len() {
+            return Err(Error::InvalidShape(format!(
+                "head and tail must have {} dimensions",
+                shape.len()
+            )));
+        }
+        if head.iter().chain(tail).any(|pad| *pad < 0) {
+            return Err(Error::InvalidShape(String::from(
+                "paddings must not be negative",
+            )));
+        }
+        let mut padded = Vec::with_capacity(shape.len());
+        for i in 0..shape.len() {
+            match shape[i]
+                .checked_add(head[i])
+                .and_then(|d| d.checked_add(tail[i]))
+            {
+                Some(dim) => padded.push(dim),
+                None => {
+                    return Err(Error::InvalidShape(format!(
+                        "padding axis {} of length {} overflows",
+                        i, shape[i]
+                    )))
+                }
+            }
+        }
+        // nn_tensor_pad always reads MAX_DIMS paddings.
+        let mut head_pad = [0; MAX_DIMS];
+        let mut tail_pad = [0; MAX_DIMS];
+        head_pad[..head.len()].copy_from_slice(head);
+        tail_pad[..tail.len()].copy_from_slice(tail);
+
+        let mut output = Tensor::new()?;
+        output.alloc(self.tensor_type(), &padded)?;
+        let ret = unsafe {
+            ffi::nn_tensor_pad(
+                output.ptr,
+                self.ptr,
+                head_pad.as_ptr(),
+                tail_pad.as_ptr(),
+                constant,
+            )
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(output)
+    }
+
+    /// Computes the paddings and output shape of a windowed operation such as
+    /// a convolution or pooling over this tensor.
+    pub fn compute_padding(
+        &self,
+        padding: Padding,
+        window: &[i32],
+        stride: &[i32],
+        dilation: &[i32],
+    ) -> Result<Paddings, Error> {
+        let n_dims = self.dims() as usize;
+        if window.len() != n_dims || stride.len() != n_dims || dilation.len() != n_dims {
+            return Err(Error::InvalidShape(format!(
+                "window, stride and dilation must have {} dimensions",
+                n_dims
+            )));
+        }
+        let padtype = CString::new(match padding {
+            Padding::Same => "SAME",
+            Padding::Valid => "VALID",
+        })
+        .unwrap();
+        let mut shape = vec![0; n_dims];
+        let mut paddings = vec![0; n_dims * 2];
+        let ret = unsafe {
+            ffi::nn_tensor_padding(
+                self.ptr,
+                padtype.as_ptr(),
+                window.as_ptr(),
+                stride.as_ptr(),
+                dilation.as_ptr(),
+                shape.as_mut_ptr(),
+                paddings.as_mut_ptr(),
+            )
+        };
+        if ret != ffi::NNError_NN_SUCCESS {
+            return Err(Error::from(ret));
+        }
+        Ok(Paddings {
+            shape,
+            head: paddings.iter().step_by(2).copied().collect(),
+            tail: paddings.iter().skip(1).step_by(2).copied().collect(),
         })
     }
 
@@ -462,6 +1327,490 @@ impl Tensor {
     }
 }
 
+/// Validates the rank and element count of `shape`, returning the volume.
+pub(crate) fn check_shape(shape: &[i32]) -> Result<i32, Error> {
+    if shape.is_empty() || shape.len() > MAX_DIMS {
+        return Err(Error::InvalidShape(format!(
+            "rank {} must be between 1 and {}",
+            shape.len(),
+            MAX_DIMS
+        )));
+    }
+    let mut volume: i32 = 1;
+    for dim in shape {
+        if *dim <= 0 {
+            return Err(Error::InvalidShape(format!(
+                "{:?} has a non-positive dimension",
+                shape
+            )));
+        }
+        volume = match volume.checked_mul(*dim) {
+            Some(volume) => volume,
+            None => {
+                return Err(Error::InvalidShape(format!(
+                    "{:?} has too many elements",
+                    shape
+                )))
+            }
+        };
+    }
+    Ok(volume)
+}
+
+/// Copies `values` into memory from [`ffi::nn_malloc`] for the library to take
+/// ownership of, returning the pointer and `own` flag.  The library tensor may
+/// outlive the wrapper which set the values, such as a context's layer tensor,
+/// so it cannot borrow them.
+fn c_copy<T: Copy>(values: &[T]) -> Result<(*const T, c_int), Error> {
+    if values.is_empty() {
+        return Ok((std::ptr::null(), 0));
+    }
+    let ptr = unsafe { ffi::nn_malloc(std::mem::size_of_val(values)) } as *mut T;
+    if ptr.is_null() {
+        return Err(Error::IoError(io::ErrorKind::OutOfMemory));
+    }
+    unsafe { std::ptr::copy_nonoverlapping(values.as_ptr(), ptr, values.len()) };
+    Ok((ptr, 1))
+}
+
+/// Computes the output shape of a slice, validating the axes and bounds.
+fn slice_shape(
+    shape: &[i32],
+    axes: &[i32],
+    head: &[i32],
+    tail: &[i32],
+    strides: &[i32],
+) -> Result<Vec<i32>, Error> {
+    if head.len() != axes.len() || tail.len() != axes.len() || strides.len() != axes.len() {
+        return Err(Error::InvalidShape(String::from(
+            "axes, head, tail and strides must have the same length",
+        )));
+    }
+    let mut shape = shape.to_vec();
+    for i in 0..axes.len() {
+        let axis = axes[i];
+        if axis < 0 || axis as usize >= shape.len() {
+            return Err(Error::InvalidShape(format!(
+                "axis {} out of range for rank {}",
+                axis,
+                shape.len()
+            )));
+        }
+        let len = shape[axis as usize];
+        if strides[i] <= 0 || head[i] < 0 || tail[i] > len || head[i] >= tail[i] {
+            return Err(Error::InvalidShape(format!(
+                "slice {}..{} step {} out of bounds for axis {} of length {}",
+                head[i], tail[i], strides[i], axis, len
+            )));
+        }
+        shape[axis as usize] = (tail[i] - head[i] - 1) / strides[i] + 1;
+    }
+    Ok(shape)
+}
+
+/// Builds and allocates a [`Tensor`], for example
+/// `Tensor::builder().dtype(TensorType::F32).shape(&[1, 224, 224, 3]).alloc()`.
+#[derive(Default)]
+pub struct TensorBuilder<'e> {
+    dtype: Option<TensorType>,
+    shape: Vec<i32>,
+    engine: Option<&'e Engine>,
+}
+
+impl<'e> TensorBuilder<'e> {
+    pub fn dtype(mut self, dtype: TensorType) -> Self {
+        self.dtype = Some(dtype);
+        self
+    }
+
+    pub fn shape(mut self, shape: &[i32]) -> Self {
+        self.shape = shape.to_vec();
+        self
+    }
+
+    /// Allocates the tensor through the engine, the tensor borrows the engine.
+    pub fn engine(mut self, engine: &'e Engine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    pub fn alloc(self) -> Result<EngineTensor<'e>, Error> {
+        let dtype = match self.dtype {
+            Some(dtype) => dtype,
+            None => return Err(Error::WrapperError(String::from("tensor dtype not set"))),
+        };
+        check_shape(&self.shape)?;
+        let mut tensor = match self.engine {
+            Some(engine) => EngineTensor::new(engine)?,
+            None => EngineTensor {
+                tensor: Tensor::new()?,
+                _engine: PhantomData,
+            },
+        };
+        tensor.alloc(dtype, &self.shape)?;
+        Ok(tensor)
+    }
+}
+
+/// Assigns `data` to `tensor` after checking it matches `shape`.
+fn assign_slice<T: TensorElement>(
+    tensor: &Tensor,
+    data: &mut [T],
+    shape: &[i32],
+) -> Result<(), Error> {
+    let volume = check_shape(shape)?;
+    if volume as usize != data.len() {
+        return Err(Error::InvalidShape(format!(
+            "{:?} requires {} elements but the buffer holds {}",
+            shape,
+            volume,
+            data.len()
+        )));
+    }
+    unsafe {
+        tensor.assign(
+            T::TYPE,
+            shape.len() as i32,
+            shape,
+            data.as_mut_ptr() as *mut c_void,
+        )
+    }
+}
+
+/// Forwards the `&mut self` methods of [`Tensor`] to the `tensor` field of a
+/// wrapper.  Wrappers must not hand out `&mut Tensor` as that would let the
+/// tensor be moved out from under the lifetime the wrapper enforces.
+macro_rules! forward_tensor_mut {
+    ($($(#[$attr:meta])* fn $name:ident$(<$gen:ident: $bound:path>)?(&mut self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$attr])*
+            #[doc = concat!("See [`Tensor::", stringify!($name), "`].")]
+            pub fn $name$(<$gen: $bound>)?(&mut self $(, $arg: $ty)*) -> $ret {
+                self.tensor.$name($($arg),*)
+            }
+        )*
+    };
+}
+
+/// Methods which replace or reinterpret the tensor's memory, not available on
+/// tensors wrapping a fixed Rust buffer.
+macro_rules! tensor_alloc_methods {
+    () => {
+        forward_tensor_mut! {
+            fn alloc(&mut self, ttype: TensorType, shape: &[i32]) -> Result<(), Error>;
+            fn set_tensor_type(&mut self, tensor_type: TensorType) -> Result<(), Error>;
+        }
+    };
+}
+
+macro_rules! tensor_mut_methods {
+    () => {
+        forward_tensor_mut! {
+            fn copy_from(&mut self, src: &Tensor) -> Result<(), Error>;
+            fn fill(&mut self, value: f64) -> Result<(), Error>;
+            fn quantize_buffer(&mut self, src: &[f32], axis: i32) -> Result<(), Error>;
+            fn from_f32_slice(&mut self, src: &[f32]) -> Result<(), Error>;
+            fn load_image(&mut self, image: &[u8], proc: ImageProc) -> Result<(), Error>;
+            fn load_image_file<P: AsRef<Path>>(&mut self, path: P, proc: ImageProc) -> Result<(), Error>;
+            fn set_scales(&mut self, scales: &[f32]) -> Result<(), Error>;
+            fn set_zeros(&mut self, zeros: &[i32]) -> Result<(), Error>;
+            fn set_axis(&mut self, axis: i32) -> Result<(), Error>;
+            fn set_quant_params(&mut self, params: &QuantParams) -> Result<(), Error>;
+            fn randomize(&mut self) -> Result<(), Error>;
+            fn maprw_u8(&mut self) -> Result<TensorDataMut<'_, u8>, Error>;
+            fn maprw_u16(&mut self) -> Result<TensorDataMut<'_, u16>, Error>;
+            fn maprw_u32(&mut self) -> Result<TensorDataMut<'_, u32>, Error>;
+            fn maprw_u64(&mut self) -> Result<TensorDataMut<'_, u64>, Error>;
+            fn maprw_i8(&mut self) -> Result<TensorDataMut<'_, i8>, Error>;
+            fn maprw_i16(&mut self) -> Result<TensorDataMut<'_, i16>, Error>;
+            fn maprw_i32(&mut self) -> Result<TensorDataMut<'_, i32>, Error>;
+            fn maprw_i64(&mut self) -> Result<TensorDataMut<'_, i64>, Error>;
+            fn maprw_f32(&mut self) -> Result<TensorDataMut<'_, f32>, Error>;
+            fn maprw_f64(&mut self) -> Result<TensorDataMut<'_, f64>, Error>;
+            #[cfg(feature = "half")]
+            fn maprw_f16(&mut self) -> Result<TensorDataMut<'_, half::f16>, Error>;
+            fn maprw<E: TensorElement>(&mut self) -> Result<TensorDataMut<'_, E>, Error>;
+            fn mapwo_u8(&mut self) -> Result<TensorDataMut<'_, u8>, Error>;
+            fn mapwo_u16(&mut self) -> Result<TensorDataMut<'_, u16>, Error>;
+            fn mapwo_u32(&mut self) -> Result<TensorDataMut<'_, u32>, Error>;
+            fn mapwo_u64(&mut self) -> Result<TensorDataMut<'_, u64>, Error>;
+            fn mapwo_i8(&mut self) -> Result<TensorDataMut<'_, i8>, Error>;
+            fn mapwo_i16(&mut self) -> Result<TensorDataMut<'_, i16>, Error>;
+            fn mapwo_i32(&mut self) -> Result<TensorDataMut<'_, i32>, Error>;
+            fn mapwo_i64(&mut self) -> Result<TensorDataMut<'_, i64>, Error>;
+            fn mapwo_f32(&mut self) -> Result<TensorDataMut<'_, f32>, Error>;
+            fn mapwo_f64(&mut self) -> Result<TensorDataMut<'_, f64>, Error>;
+            #[cfg(feature = "half")]
+            fn mapwo_f16(&mut self) -> Result<TensorDataMut<'_, half::f16>, Error>;
+            fn mapwo<E: TensorElement>(&mut self) -> Result<TensorDataMut<'_, E>, Error>;
+            fn map_bytes_mut(&mut self) -> Result<TensorDataMut<'_, u8>, Error>;
+            fn view(&mut self, dtype: TensorType, shape: &[i32], offset: i32) -> Result<TensorView<'_>, Error>;
+            fn reshape(&mut self, shape: &[i32]) -> Result<(), Error>;
+        }
+    };
+}
+
+/// Exclusive access to a tensor owned elsewhere, such as a context's layer
+/// tensors.  Unlike `&mut Tensor` the handle cannot move the tensor out of its
+/// owner.
+pub struct TensorMut<'t> {
+    tensor: &'t mut Tensor,
+}
+
+impl TensorMut<'_> {
+    tensor_alloc_methods!();
+
+    tensor_mut_methods!();
+}
+
+impl<'t> From<&'t mut Tensor> for TensorMut<'t> {
+    fn from(tensor: &'t mut Tensor) -> Self {
+        TensorMut { tensor }
+    }
+}
+
+impl Deref for TensorMut<'_> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Self::Target {
+        self.tensor
+    }
+}
+
+/// A tensor wrapping a borrowed Rust buffer without copying it.  The buffer
+/// cannot be accessed or freed while the tensor exists.
+pub struct BorrowedTensor<'a> {
+    tensor: Tensor,
+    _data: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> BorrowedTensor<'a> {
+    tensor_mut_methods!();
+
+    /// Wraps `data` as a tensor of `shape`, the tensor type is taken from the
+    /// element type.
+    pub fn new<T: TensorElement>(data: &'a mut [T], shape: &[i32]) -> Result<Self, Error> {
+        let tensor = Tensor::new()?;
+        assign_slice(&tensor, data, shape)?;
+        Ok(BorrowedTensor {
+            tensor,
+            _data: PhantomData,
+        })
+    }
+}
+
+impl Deref for BorrowedTensor<'_> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tensor
+    }
+}
+
+/// A tensor managed by an engine, which the tensor borrows so the engine
+/// cannot be released first.
+pub struct EngineTensor<'e> {
+    tensor: Tensor,
+    _engine: PhantomData<&'e Engine>,
+}
+
+impl<'e> EngineTensor<'e> {
+    tensor_alloc_methods!();
+
+    tensor_mut_methods!();
+
+    pub fn new(engine: &'e Engine) -> Result<Self, Error> {
+        Ok(EngineTensor {
+            tensor: unsafe { Tensor::with_engine(engine)? },
+            _engine: PhantomData,
+        })
+    }
+}
+
+impl Deref for EngineTensor<'_> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tensor
+    }
+}
+
+impl<'t> From<&'t mut EngineTensor<'_>> for TensorMut<'t> {
+    fn from(tensor: &'t mut EngineTensor<'_>) -> Self {
+        TensorMut {
+            tensor: &mut tensor.tensor,
+        }
+    }
+}
+
+/// A tensor sharing the memory of a parent tensor, created by [`Tensor::view`].
+pub struct TensorView<'p> {
+    tensor: Tensor,
+    _parent: PhantomData<&'p mut Tensor>,
+}
+
+impl Deref for TensorView<'_> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tensor
+    }
+}
+
+impl TensorView<'_> {
+    tensor_mut_methods!();
+}
+
+/// A tensor which owns the Rust buffer backing it, keeping the buffer alive
+/// for the tensor's lifetime.
+pub struct OwnedTensor<T: TensorElement> {
+    // Declared before data so the tensor is released before its buffer.
+    tensor: Tensor,
+    data: Box<[T]>,
+}
+
+impl<T: TensorElement> OwnedTensor<T> {
+    tensor_mut_methods!();
+
+    /// Wraps `data`, such as a `Vec<T>` or `Box<[T]>`, as a tensor of `shape`.
+    pub fn new<D: Into<Box<[T]>>>(data: D, shape: &[i32]) -> Result<Self, Error> {
+        let mut data = data.into();
+        let tensor = Tensor::new()?;
+        assign_slice(&tensor, &mut data, shape)?;
+        Ok(OwnedTensor { tensor, data })
+    }
+
+    /// Releases the tensor and returns the buffer which backed it.
+    pub fn into_inner(self) -> Box<[T]> {
+        let OwnedTensor { tensor, data } = self;
+        drop(tensor);
+        data
+    }
+}
+
+impl<T: TensorElement> Deref for OwnedTensor<T> {
+    type Target = Tensor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tensor
+    }
+}
+
+#[cfg(feature = "half")]
+impl Tensor {
+    /// Converts an F16 tensor into a newly allocated F32 tensor of the same
+    /// shape.
+    pub fn to_f32(&self) -> Result<Tensor, Error> {
+        use half::slice::HalfFloatSliceExt;
+
+        let mut dest = Tensor::new()?;
+        dest.alloc(TensorType::F32, self.shape())?;
+        {
+            let src = self.mapro::<half::f16>()?;
+            let mut dst = dest.mapwo::<f32>()?;
+            src.convert_to_f32_slice(&mut dst);
+        }
+        Ok(dest)
+    }
+
+    /// Converts an F32 tensor into a newly allocated F16 tensor of the same
+    /// shape.
+    pub fn to_f16(&self) -> Result<Tensor, Error> {
+        use half::slice::HalfFloatSliceExt;
+
+        let mut dest = Tensor::new()?;
+        dest.alloc(TensorType::F16, self.shape())?;
+        {
+            let src = self.mapro::<f32>()?;
+            let mut dst = dest.mapwo::<half::f16>()?;
+            dst.convert_from_f32_slice(&src);
+        }
+        Ok(dest)
+    }
+}
+
+/// The array shape of the mapped tensor data.  Mappings cover `volume`
+/// contiguous elements which are assumed to be laid out in row-major order.
+#[cfg(feature = "ndarray")]
+fn array_shape(tensor: &Tensor) -> ndarray::IxDyn {
+    let shape: Vec<usize> = tensor.shape().iter().map(|d| *d as usize).collect();
+    ndarray::IxDyn(&shape)
+}
+
+#[cfg(feature = "ndarray")]
+impl<T> TensorData<'_, T> {
+    /// Views the mapped data as an array of the tensor's shape, without
+    /// copying.  The data is assumed to be contiguous and row-major.
+    pub fn view(&self) -> Result<ndarray::ArrayViewD<'_, T>, Error> {
+        match ndarray::ArrayViewD::from_shape(array_shape(self.tensor), self.data) {
+            Ok(view) => Ok(view),
+            Err(e) => Err(Error::InvalidShape(e.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T> TensorDataMut<'_, T> {
+    /// Views the mapped data as an array of the tensor's shape, without
+    /// copying.  The data is assumed to be contiguous and row-major.
+    pub fn view(&self) -> Result<ndarray::ArrayViewD<'_, T>, Error> {
+        match ndarray::ArrayViewD::from_shape(array_shape(self.tensor), self.data) {
+            Ok(view) => Ok(view),
+            Err(e) => Err(Error::InvalidShape(e.to_string())),
+        }
+    }
+
+    /// Mutably views the mapped data as an array of the tensor's shape, without
+    /// copying.  The data is assumed to be contiguous and row-major.
+    pub fn view_mut(&mut self) -> Result<ndarray::ArrayViewMutD<'_, T>, Error> {
+        match ndarray::ArrayViewMutD::from_shape(array_shape(self.tensor), self.data) {
+            Ok(view) => Ok(view),
+            Err(e) => Err(Error::InvalidShape(e.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl Tensor {
+    /// Allocates a tensor with the array's shape and copies the array into it.
+    pub fn from_array<T: TensorElement>(array: &ndarray::ArrayD<T>) -> Result<Tensor, Error> {
+        let shape: Vec<i32> = array.shape().iter().map(|d| *d as i32).collect();
+        let mut tensor = Tensor::new()?;
+        tensor.alloc(T::TYPE, &shape)?;
+        if tensor.volume() as usize != array.len() {
+            return Err(Error::ShapeMismatch(format!(
+                "tensor volume {} does not match array length {}",
+                tensor.volume(),
+                array.len()
+            )));
+        }
+        {
+            let mut data = tensor.mapwo::<T>()?;
+            for (dst, src) in data.iter_mut().zip(array.iter()) {
+                *dst = *src;
+            }
+        }
+        Ok(tensor)
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<'a> BorrowedTensor<'a> {
+    /// Wraps a contiguous, standard layout array view as a tensor without
+    /// copying, for example `BorrowedTensor::from_array(array.view_mut())`.
+    pub fn from_array<T: TensorElement>(
+        array: ndarray::ArrayViewMutD<'a, T>,
+    ) -> Result<Self, Error> {
+        let shape: Vec<i32> = array.shape().iter().map(|d| *d as i32).collect();
+        match array.into_slice() {
+            Some(data) => BorrowedTensor::new(data, &shape),
+            None => Err(Error::InvalidShape(String::from(
+                "array is not contiguous in standard layout",
+            ))),
+        }
+    }
+}
+
 impl Drop for Tensor {
     fn drop(&mut self) {
         if self.owned {
@@ -483,3 +1832,66 @@ impl<T> Drop for TensorDataMut<'_, T> {
         unsafe { self.tensor.unmap() };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_shape_volume() {
+        assert_eq!(check_shape(&[4]).unwrap(), 4);
+        assert_eq!(check_shape(&[1, 224, 224, 3]).unwrap(), 150528);
+    }
+
+    #[test]
+    fn check_shape_invalid() {
+        assert!(check_shape(&[]).is_err());
+        assert!(check_shape(&[1, 2, 3, 4, 5]).is_err());
+        assert!(check_shape(&[1, 0, 3]).is_err());
+        assert!(check_shape(&[1, -2]).is_err());
+        assert!(check_shape(&[i32::MAX, 2]).is_err());
+    }
+
+    #[test]
+    fn slice_shape_bounds() {
+        let shape = [1, 10, 8, 3];
+        assert_eq!(
+            slice_shape(&shape, &[1, 2], &[2, 0], &[7, 8], &[1, 1]).unwrap(),
+            [1, 5, 8, 3]
+        );
+        assert_eq!(
+            slice_shape(&shape, &[], &[], &[], &[]).unwrap(),
+            [1, 10, 8, 3]
+        );
+        assert!(slice_shape(&shape, &[1], &[0], &[11], &[1]).is_err());
+        assert!(slice_shape(&shape, &[1], &[-1], &[5], &[1]).is_err());
+        assert!(slice_shape(&shape, &[1], &[5], &[5], &[1]).is_err());
+        assert!(slice_shape(&shape, &[4], &[0], &[1], &[1]).is_err());
+        assert!(slice_shape(&shape, &[-1], &[0], &[1], &[1]).is_err());
+        assert!(slice_shape(&shape, &[1, 2], &[0], &[1], &[1]).is_err());
+    }
+
+    #[test]
+    fn slice_shape_strides() {
+        let shape = [1, 10, 8, 3];
+        assert_eq!(
+            slice_shape(&shape, &[1, 2], &[0, 1], &[10, 8], &[3, 2]).unwrap(),
+            [1, 4, 4, 3]
+        );
+        assert_eq!(
+            slice_shape(&shape, &[1], &[0], &[10], &[i32::MAX]).unwrap(),
+            [1, 1, 8, 3]
+        );
+        assert!(slice_shape(&shape, &[1], &[0], &[10], &[0]).is_err());
+        assert!(slice_shape(&shape, &[1], &[0], &[10], &[-1]).is_err());
+    }
+
+    #[test]
+    fn image_proc_flags() {
+        let proc = ImageProc::SIGNED_NORM | ImageProc::MIRROR;
+        assert_eq!(proc.bits(), 0x1004);
+        assert!(proc.contains(ImageProc::MIRROR));
+        assert!(!proc.contains(ImageProc::FLIP));
+        assert_eq!(ImageProc::default(), ImageProc::NONE);
+    }
+}